@@ -0,0 +1,269 @@
+use std::f64::consts::PI;
+
+use ratatui::widgets::ListItem;
+use ratatui::
+    style::{
+        Color,
+        Stylize
+    };
+
+use crate::eos::new_eos;
+use crate::flash::{
+    self,
+    GasProps,
+};
+use crate::units::{
+    self,
+    PrintUnit,
+};
+use crate::App;
+
+// Carbon steel wall heat capacity in J/(kg-K)
+pub const WALL_CP: f64 = 500.0;
+// Fraction of the vessel inventory vented per time step
+const STEP_FRACTION: f64 = 0.01;
+const MAX_STEPS: usize = 5000;
+const MAX_TIME: f64 = 86400.0;
+const TABLE_ROWS: usize = 12;
+
+pub struct Sample {
+    // Elapsed time in s
+    pub time: f64,
+    pub p: f64,
+    pub t: f64,
+    pub t_wall: f64,
+    // Vessel contents in kg
+    pub mass: f64,
+    // Vent flow in kg/s
+    pub flow: f64,
+}
+
+pub struct BlowdownResult {
+    pub samples: Vec<Sample>,
+    pub time_to_target: Option<f64>,
+    pub min_gas_temp: f64,
+    pub min_wall_temp: f64,
+}
+
+// Vent mass flux (kg/(m^2-s)) through an isentropic nozzle using the real
+// gas cp/cv and density of the vessel contents
+pub fn vent_mass_flux(state: &GasProps, back_p: f64) -> f64 {
+    if state.p <= back_p {
+        return 0.0
+    }
+    let k = state.cp / state.cv;
+    let rho = state.d * state.mm;
+    let p = state.p * 1000.0;
+    let r_critical = (2.0 / (k + 1.0)).powf(k / (k - 1.0));
+    let r = back_p / state.p;
+    if r <= r_critical {
+        (k * p * rho * (2.0 / (k + 1.0)).powf((k + 1.0) / (k - 1.0))).sqrt()
+    } else {
+        (2.0 * p * rho * k / (k - 1.0) * (r.powf(2.0 / k) - r.powf((k + 1.0) / k))).sqrt()
+    }
+}
+
+// Inside wall area (m^2) of a cylindrical vessel with length three times the
+// diameter and flat heads
+pub fn wall_area(volume: f64) -> f64 {
+    let d = (4.0 * volume / (3.0 * PI)).cbrt();
+    3.5 * PI * d * d
+}
+
+// Vessel contents as a real gas control volume: the mole and internal energy
+// balances give density and internal energy each step, and the U-V flash
+// gives the new state
+pub fn blowdown(app: &App) -> BlowdownResult {
+    let (p0, t0) = (app.cur_state.p, app.cur_state.t);
+    let mut eos = new_eos(app.model, &app.gas_comp);
+    let mut state = eos.props_pt(p0, t0);
+    let volume_l = app.blowdown_volume * 1000.0;
+    let orifice_area = PI / 4.0 * app.blowdown_orifice_d * app.blowdown_orifice_d;
+    let area = wall_area(app.blowdown_volume);
+    let heat_transfer = app.blowdown_wall_mass > 0.0;
+
+    let mut moles = state.d * volume_l;
+    let mut energy = moles * state.u;
+    let mut t_wall = t0;
+    let mut time = 0.0;
+    let mut time_to_target = None;
+    let mut min_gas_temp = t0;
+    let mut min_wall_temp = t0;
+    let mut samples = vec![];
+
+    for _ in 0..MAX_STEPS {
+        let flow = app.blowdown_cd * orifice_area * vent_mass_flux(&state, app.blowdown_back_p);
+        samples.push(Sample {
+            time,
+            p: state.p,
+            t: state.t,
+            t_wall,
+            mass: moles * state.mm / 1000.0,
+            flow,
+        });
+        if time_to_target.is_none() && state.p <= app.blowdown_target_p {
+            time_to_target = Some(time);
+        }
+        if flow <= 0.0 || state.p <= 1.01 * app.blowdown_back_p || time >= MAX_TIME {
+            break;
+        }
+
+        // Molar vent rate in mol/s and wall heat input in W
+        let vent = flow / state.mm * 1000.0;
+        let dt = STEP_FRACTION * moles / vent;
+        let q = if heat_transfer { app.blowdown_h * area * (t_wall - state.t) } else { 0.0 };
+
+        moles -= vent * dt;
+        energy += (q - vent * state.h) * dt;
+        if heat_transfer {
+            t_wall -= q * dt / (app.blowdown_wall_mass * WALL_CP);
+        }
+        time += dt;
+        state = flash::solve_du(eos.as_mut(), moles / volume_l, energy / moles, state.t);
+        min_gas_temp = min_gas_temp.min(state.t);
+        min_wall_temp = min_wall_temp.min(t_wall);
+    }
+
+    BlowdownResult {
+        samples,
+        time_to_target,
+        min_gas_temp,
+        min_wall_temp,
+    }
+}
+
+// Mass and volume in the selected unit system
+pub fn get_mass(app: &App, kg: f64) -> (f64, &'static str) {
+    if app.units.flow.is_imperial() { (kg * 2.20462, "lbm") } else { (kg, "kg") }
+}
+
+pub fn get_volume(app: &App, m3: f64) -> (f64, &'static str) {
+    if app.units.flow.is_imperial() { (m3 * 35.3147, "ft^3") } else { (m3, "m^3") }
+}
+
+pub fn set_volume(app: &App, volume: f64) -> f64 {
+    if app.units.flow.is_imperial() { volume / 35.3147 } else { volume }
+}
+
+pub fn set_param(app: &mut App, val: f64) {
+    match app.input_param {
+        1 => app.blowdown_volume = set_volume(app, val),
+        2 => app.blowdown_orifice_d = units::set_length(val, app.units.length),
+        3 => app.blowdown_cd = val,
+        4 => app.blowdown_back_p = units::set_pressure(val, app.units.pressure),
+        5 => app.blowdown_target_p = units::set_pressure(val, app.units.pressure),
+        6 => app.blowdown_wall_mass = if app.units.flow.is_imperial() { val / 2.20462 } else { val },
+        7 => app.blowdown_h = val,
+        _ => {}
+    }
+}
+
+pub fn run_blowdown(app: &App) -> Vec<ListItem<'static>> {
+    let p_str = app.units.pressure.print_unit();
+    let t_str = app.units.temp.print_unit();
+    let flow_str = app.units.flow.print_unit();
+    let (volume, volume_str) = get_volume(app, app.blowdown_volume);
+    let (wall_mass, mass_str) = get_mass(app, app.blowdown_wall_mass);
+
+    let inputs = [
+        ("1-Vessel Volume:", volume, volume_str),
+        ("2-Orifice Diameter:", units::get_length(app.blowdown_orifice_d, app.units.length), app.units.length.print_unit()),
+        ("3-Discharge Coeff:", app.blowdown_cd, "[]"),
+        ("4-Back Pressure:", units::get_pressure(app.blowdown_back_p, app.units.pressure), p_str),
+        ("5-Target Pressure:", units::get_pressure(app.blowdown_target_p, app.units.pressure), p_str),
+        ("6-Wall Mass (0=adiab.):", wall_mass, mass_str),
+        ("7-Inside Film Coeff:", app.blowdown_h, "W/(m^2-K)"),
+    ];
+    let mut items: Vec<ListItem<'static>> = inputs
+        .iter()
+        .map(|(label, val, unit)| {
+            ListItem::new(
+                format!("{:<24} {:.4} {}",
+                    label, val, unit,
+                )
+            )
+                .fg(Color::LightYellow)
+                .bg(Color::Black)
+        })
+        .collect();
+
+    if app.blowdown_volume <= 0.0 || app.blowdown_orifice_d <= 0.0 {
+        items.push(
+            ListItem::new("Enter the vessel volume (1) and orifice diameter (2)")
+                .fg(Color::Red)
+                .bg(Color::Black)
+        );
+        return items
+    }
+
+    let result = blowdown(app);
+    let target_text = match result.time_to_target {
+        Some(time) => format!("{:.1} s", time),
+        None => "Not reached".to_string(),
+    };
+    items.push(
+        ListItem::new(
+            format!("{:<24} {}",
+                "Time to Target:", target_text,
+            )
+        )
+            .fg(Color::LightCyan)
+            .bg(Color::Black)
+    );
+    items.push(
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Min Gas Temp:", units::get_temperature(result.min_gas_temp, app.units.temp), t_str,
+            )
+        )
+            .fg(Color::LightCyan)
+            .bg(Color::Black)
+    );
+    items.push(
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Min Wall Temp:", units::get_temperature(result.min_wall_temp, app.units.temp), t_str,
+            )
+        )
+            .fg(Color::LightCyan)
+            .bg(Color::Black)
+    );
+
+    items.push(
+        ListItem::new(
+            format!("{:<10} {:<14} {:<12} {:<12} {:<14} Flow ({})",
+                "Time (s)",
+                format!("Press ({})", p_str),
+                format!("Gas ({})", t_str),
+                format!("Wall ({})", t_str),
+                format!("Mass ({})", get_mass(app, 0.0).1),
+                flow_str,
+            )
+        )
+            .fg(Color::White)
+            .bg(Color::Black)
+    );
+    let end_time = result.samples.last().map(|sample| sample.time).unwrap_or(0.0);
+    let mut next_time = 0.0;
+    for (i, sample) in result.samples.iter().enumerate() {
+        if sample.time < next_time && i + 1 != result.samples.len() {
+            continue;
+        }
+        next_time = sample.time + end_time / TABLE_ROWS as f64;
+        items.push(
+            ListItem::new(
+                format!("{:<10.1} {:<14.4} {:<12.4} {:<12.4} {:<14.4} {:.4}",
+                    sample.time,
+                    units::get_pressure(sample.p, app.units.pressure),
+                    units::get_temperature(sample.t, app.units.temp),
+                    units::get_temperature(sample.t_wall, app.units.temp),
+                    get_mass(app, sample.mass).0,
+                    units::get_flow(sample.flow, app.units.flow, &app.gas_comp, app.stp_60_F, app.model),
+                )
+            )
+                .fg(Color::LightCyan)
+                .bg(Color::Black)
+        );
+    }
+    items
+}
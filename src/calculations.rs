@@ -1,6 +1,4 @@
 
-use aga8::gerg2008::Gerg2008;
-use aga8::detail::Detail;
 use ratatui::widgets::ListItem;
 use ratatui::
     style::{
@@ -14,112 +12,70 @@ use crate::units::{
     set_temperature,
     get_temperature,
 };
+use crate::flash;
 use crate::App;
 
 pub fn density_ratio(app: &App) -> f64 {
-    if app.use_gerg2008 {
-        app.gerg_outlet_state.d / app.gerg_inlet_state.d
-    } else {
-        app.aga8_outlet_state.d / app.aga8_inlet_state.d
-    }
+    app.outlet_state.d / app.inlet_state.d
 }
 
 pub fn pressure_ratio(app: &App) -> f64 {
-    if app.use_gerg2008 {
-        app.gerg_outlet_state.p / app.gerg_inlet_state.p
-    } else {
-        app.aga8_outlet_state.p / app.aga8_inlet_state.p
-    }
+    app.outlet_state.p / app.inlet_state.p
 }
 
 pub fn temperature_ratio(app: &App) -> f64 {
-    if app.use_gerg2008 {
-        app.gerg_outlet_state.t / app.gerg_inlet_state.t
-    } else {
-        app.aga8_outlet_state.t / app.aga8_inlet_state.t
-    }
+    app.outlet_state.t / app.inlet_state.t
 }
 
 pub fn temperature_change(app: &App) -> f64 {
-    let t1;
-    let t2;
-    if app.use_gerg2008 {
-        t1 = app.gerg_inlet_state.t;
-        t2 = app.gerg_outlet_state.t;
-    } else {
-        t1 = app.aga8_inlet_state.t;
-        t2 = app.aga8_outlet_state.t;
-    }
-    let t1 = units::get_temperature(t1, app.units.temp);
-    let t2 = units::get_temperature(t2, app.units.temp);
+    let t1 = units::get_temperature(app.inlet_state.t, app.units.temp);
+    let t2 = units::get_temperature(app.outlet_state.t, app.units.temp);
     t2 - t1
 }
 
 pub fn enthalpy_change(app: &App) -> f64 {
-    if app.use_gerg2008 {
-        app.gerg_outlet_state.h - app.gerg_inlet_state.h
-    } else {
-        app.aga8_outlet_state.h - app.aga8_inlet_state.h
-    }
+    app.outlet_state.h - app.inlet_state.h
 }
 
 pub fn entropy_change(app: &App) -> f64 {
-    if app.use_gerg2008 {
-        app.gerg_outlet_state.s - app.gerg_inlet_state.s
-    } else {
-        app.aga8_outlet_state.s - app.aga8_inlet_state.s
-    }
+    app.outlet_state.s - app.inlet_state.s
 }
 
 pub fn ave_cp_cv(app: &App) -> f64 {
-    if app.use_gerg2008 {
-        (app.gerg_outlet_state.kappa + app.gerg_inlet_state.kappa) / 2.0
-    } else {
-        (app.aga8_outlet_state.kappa + app.aga8_inlet_state.kappa) / 2.0
-    }
+    (app.outlet_state.kappa + app.inlet_state.kappa) / 2.0
 }
 
 pub fn isentropic_eff(app: &mut App, hs: f64) -> f64 {
-    if app.use_gerg2008 {
-        let pr = app.gerg_outlet_state.p / app.gerg_inlet_state.p;
-        if pr >= 1.0 {
-            let hd = app.gerg_outlet_state.h - app.gerg_inlet_state.h;
-            let hds = hs - app.gerg_inlet_state.h;
-            if hd == 0.0 {
-                return 0.0
-            }
-            hds / hd
-        } else {
-            let hd = app.gerg_inlet_state.h - app.gerg_outlet_state.h;
-            let hds = app.gerg_inlet_state.h - hs;
-            if hds == 0.0 {
-                return 0.0
-            }
-            hd / hds
+    let pr = app.outlet_state.p / app.inlet_state.p;
+    if pr >= 1.0 {
+        let hd = app.outlet_state.h - app.inlet_state.h;
+        let hds = hs - app.inlet_state.h;
+        if hd == 0.0 {
+            return 0.0
         }
+        hds / hd
     } else {
-        let pr = app.aga8_outlet_state.p / app.aga8_inlet_state.p;
-        if pr >= 1.0 {
-            let hd = app.aga8_outlet_state.h - app.aga8_inlet_state.h;
-            if hd == 0.0 {
-                return 0.0
-            }
-            let hds = hs - app.aga8_inlet_state.h;
-            hds / hd
-        } else {
-            let hd = app.aga8_inlet_state.h - app.aga8_outlet_state.h;
-            let hds = app.aga8_inlet_state.h - hs;
-            if hds == 0.0 {
-                return 0.0
-            }
-            hd / hds
+        let hd = app.inlet_state.h - app.outlet_state.h;
+        let hds = app.inlet_state.h - hs;
+        if hds == 0.0 {
+            return 0.0
         }
+        hd / hds
     }
 }
 
+// Gas power in kW from the enthalpy change and mass flow
 pub fn work(app: &mut App) -> f64 {
     let hd = enthalpy_change(app);
-    hd * app.flow_val
+    let mm = app.inlet_state.mm;
+    hd / mm * app.flow_val
+}
+
+// Isentropic head in J/mol
+pub fn isentropic_head(app: &mut App) -> f64 {
+    let ts = isentropic_temp(app);
+    let hs = isentropic_enthalpy(app, ts);
+    isentropic_enthalpy_change(app, hs)
 }
 
 pub fn tip_speed(app: &mut App) -> f64 {
@@ -128,61 +84,18 @@ pub fn tip_speed(app: &mut App) -> f64 {
 }
 
 pub fn isentropic_temp(app: &mut App) -> f64 {
-    let t1;
-    let t2s;
-    let p1;
-    let p2;
-    let k;
-    if app.use_gerg2008 {
-        t1 = app.gerg_inlet_state.t;
-        p1 = app.gerg_inlet_state.p;
-        p2 = app.gerg_outlet_state.p;
-        k = (app.gerg_inlet_state.kappa + app.gerg_outlet_state.kappa) / 2.0;
-        let pr = p2 / p1;
-        t2s = t1 * pr.powf((k-1.0)/k);
-    } else {
-        t1 = app.aga8_inlet_state.t;
-        p1 = app.aga8_inlet_state.p;
-        p2 = app.aga8_outlet_state.p;
-        k = (app.aga8_inlet_state.kappa + app.aga8_outlet_state.kappa) / 2.0;
-        let pr = p2 / p1;
-        t2s = t1 * pr.powf((k-1.0)/k);
-    }
-    t2s
+    let t1 = app.inlet_state.t;
+    let k = (app.inlet_state.kappa + app.outlet_state.kappa) / 2.0;
+    let pr = app.outlet_state.p / app.inlet_state.p;
+    t1 * pr.powf((k-1.0)/k)
 }
 
 pub fn isentropic_enthalpy(app: &mut App, ts: f64) -> f64 {
-    let hs;
-    if app.use_gerg2008 {
-        let mut gas_state = Gerg2008::new();
-        gas_state.set_composition(&app.gas_comp);
-        gas_state.p = app.gerg_outlet_state.p;
-        gas_state.t = ts;
-        gas_state.density(0);
-        gas_state.properties();
-        hs = gas_state.h;
-    } else {
-        let mut gas_state = Detail::new();
-        gas_state.set_composition(&app.gas_comp);
-        gas_state.p = app.aga8_outlet_state.p;
-        gas_state.t = ts;
-        gas_state.density();
-        gas_state.properties();
-        hs = gas_state.h;
-    }
-    hs
+    flash::props_pt(&app.gas_comp, app.model, app.outlet_state.p, ts).h
 }
 
 pub fn isentropic_enthalpy_change(app: &mut App, hs: f64) -> f64 {
-    let hds;
-    if app.use_gerg2008 {
-        let h1 = app.gerg_inlet_state.h;
-        hds = hs - h1;
-    } else {
-        let h1 = app.aga8_inlet_state.h;
-        hds = hs - h1;
-    }
-    hds
+    hs - app.inlet_state.h
 }
 
 pub fn run_calculations(app: &mut App) -> [Vec<ListItem<'_>>; 3] {
@@ -198,9 +111,11 @@ pub fn run_calculations(app: &mut App) -> [Vec<ListItem<'_>>; 3] {
         app.units.flow, 
         &app.gas_comp, 
         app.stp_60_F, 
-        app.use_gerg2008
+        app.model
     );
     let tip_speed_val = tip_speed(app);
+    let mm = app.inlet_state.mm;
+    let head = units::get_energy(hds, app.units.energy, mm);
 
     let efficiency_color;
         if isentropic_efficiency > 1.0 || isentropic_efficiency < 0.0 {
@@ -236,7 +151,7 @@ pub fn run_calculations(app: &mut App) -> [Vec<ListItem<'_>>; 3] {
 
         ListItem::new(
             format!("{:<18} {:.4} {:>}", 
-                "Enthalpy Change:", hd, app.units.energy.print_unit(),
+                "Enthalpy Change:", units::get_energy(hd, app.units.energy, mm), app.units.energy.print_unit(),
             )
         )
         .fg(Color::LightCyan)
@@ -270,7 +185,7 @@ pub fn run_calculations(app: &mut App) -> [Vec<ListItem<'_>>; 3] {
 
         ListItem::new(
             format!("{:<18} {:.4} {:>}", 
-                "Enthalpy Hs:", hs, app.units.energy.print_unit(),
+                "Enthalpy Hs:", units::get_energy(hs + app.ref_offset.h, app.units.energy, mm), app.units.energy.print_unit(),
             )
         )
             .fg(Color::LightCyan)
@@ -286,7 +201,7 @@ pub fn run_calculations(app: &mut App) -> [Vec<ListItem<'_>>; 3] {
 
         ListItem::new(
             format!("{:<18} {:.4} {:>}", 
-                "Enthalpy Change:", hds, app.units.energy.print_unit(),
+                "Enthalpy Change:", units::get_energy(hds, app.units.energy, mm), app.units.energy.print_unit(),
             )
         )
         .fg(Color::LightCyan)
@@ -355,7 +270,7 @@ pub fn run_calculations(app: &mut App) -> [Vec<ListItem<'_>>; 3] {
 
         ListItem::new(
             format!("{:<18} {:.4} {:>}", 
-                "Head:", "TBD", app.units.energy.print_unit(),
+                "Head:", head, app.units.energy.print_unit(),
             )
         )
         .fg(Color::LightYellow)
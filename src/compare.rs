@@ -0,0 +1,237 @@
+use ratatui::widgets::ListItem;
+use ratatui::
+    style::{
+        Color,
+        Stylize
+    };
+
+use crate::calculations;
+use crate::eos::Model;
+use crate::flash::{
+    self,
+    GasProps,
+};
+use crate::transport;
+use crate::units::{
+    self,
+    PrintUnit,
+};
+use crate::App;
+
+// Reference model the compared model is measured against
+const REFERENCE: Model = Model::Gerg2008;
+
+pub struct Row {
+    pub label: &'static str,
+    pub value: f64,
+    pub reference: f64,
+    pub unit: &'static str,
+}
+
+impl Row {
+    pub fn deviation(&self) -> f64 {
+        self.value - self.reference
+    }
+
+    // Percent deviation of the compared model from the reference
+    pub fn percent_deviation(&self) -> f64 {
+        if self.reference == 0.0 {
+            return 0.0
+        }
+        (self.value - self.reference) / self.reference.abs() * 100.0
+    }
+}
+
+// Next model to compare, skipping the reference itself
+pub fn next_model(model: Model) -> Model {
+    let next = model.next();
+    if next == REFERENCE {
+        next.next()
+    } else {
+        next
+    }
+}
+
+// Property values in display units for one state, in the order of the
+// state panels
+fn state_values(app: &App, model: Model, state: &GasProps) -> [f64; 14] {
+    let props = app.ref_offset.apply(&flash::props_pt(&app.gas_comp, model, state.p, state.t));
+    let tp = transport::transport_properties(&app.gas_comp, model, props.t, props.d, props.mm, props.cp);
+    [
+        units::get_density(props.d, app.units.density, props.mm),
+        units::get_energy(props.u, app.units.energy, props.mm),
+        units::get_energy(props.h, app.units.energy, props.mm),
+        units::get_entropy(props.s, app.units.entropy, props.mm),
+        units::get_entropy(props.cp, app.units.entropy, props.mm),
+        units::get_entropy(props.cv, app.units.entropy, props.mm),
+        props.kappa,
+        props.z,
+        units::get_speed(props.w, app.units.speed),
+        units::get_jt_coeff(props.jt, app.units.jt_coeff),
+        units::get_viscosity(tp.viscosity, app.units.viscosity),
+        units::get_kinematic_viscosity(tp.kinematic_viscosity, app.units.viscosity),
+        units::get_conductivity(tp.conductivity, app.units.conductivity),
+        tp.prandtl,
+    ]
+}
+
+fn rows(labels: &[(&'static str, &'static str)], values: &[f64], reference: &[f64]) -> Vec<Row> {
+    labels
+        .iter()
+        .zip(values.iter().zip(reference.iter()))
+        .map(|((label, unit), (value, reference))| Row {
+            label,
+            value: *value,
+            reference: *reference,
+            unit,
+        })
+        .collect()
+}
+
+fn state_rows(app: &App, state: &GasProps) -> Vec<Row> {
+    let labels = [
+        ("Density:", app.units.density.print_unit()),
+        ("Internal Energy:", app.units.energy.print_unit()),
+        ("Enthalpy:", app.units.energy.print_unit()),
+        ("Entropy:", app.units.entropy.print_unit()),
+        ("Cp:", app.units.entropy.print_unit()),
+        ("Cv:", app.units.entropy.print_unit()),
+        ("Cp/Cv (k):", "[]"),
+        ("Z:", "[]"),
+        ("Speed of Sound:", app.units.speed.print_unit()),
+        ("JT Coeff:", app.units.jt_coeff.print_unit()),
+        ("Viscosity:", app.units.viscosity.print_unit()),
+        ("Kin. Viscosity:", app.units.viscosity.print_kinematic_unit()),
+        ("Conductivity:", app.units.conductivity.print_unit()),
+        ("Prandtl No:", "[]"),
+    ];
+    let values = state_values(app, app.compare_model, state);
+    let reference = state_values(app, REFERENCE, state);
+    rows(&labels, &values, &reference)
+}
+
+// State change and isentropic calculations in display units; the
+// calculation functions read the model and the inlet and outlet states, so
+// these are set for each model and restored afterwards
+pub fn derived_values(app: &mut App, model: Model) -> [f64; 7] {
+    let saved = (app.model, app.inlet_state, app.outlet_state);
+    app.model = model;
+    app.inlet_state = flash::props_pt(&app.gas_comp, model, saved.1.p, saved.1.t);
+    app.outlet_state = flash::props_pt(&app.gas_comp, model, saved.2.p, saved.2.t);
+    let mm = app.inlet_state.mm;
+    let ts = calculations::isentropic_temp(app);
+    let hs = calculations::isentropic_enthalpy(app, ts);
+    let hds = calculations::isentropic_enthalpy_change(app, hs);
+    let values = [
+        calculations::temperature_change(app),
+        units::get_energy(calculations::enthalpy_change(app), app.units.energy, mm),
+        units::get_entropy(calculations::entropy_change(app), app.units.entropy, mm),
+        calculations::ave_cp_cv(app),
+        units::get_energy(hds, app.units.energy, mm),
+        calculations::isentropic_eff(app, hs),
+        units::get_power(calculations::work(app), app.units.power),
+    ];
+    (app.model, app.inlet_state, app.outlet_state) = saved;
+    values
+}
+
+pub fn derived_labels(app: &App) -> [(&'static str, &'static str); 7] {
+    [
+        ("Temp Change:", app.units.temp.print_unit()),
+        ("Enthalpy Change:", app.units.energy.print_unit()),
+        ("Entropy Change:", app.units.entropy.print_unit()),
+        ("Ave Cp/Cv:", "[]"),
+        ("Isentropic Head:", app.units.energy.print_unit()),
+        ("Efficiency:", "[]"),
+        ("Gas Power:", app.units.power.print_unit()),
+    ]
+}
+
+fn derived_rows(app: &mut App) -> Vec<Row> {
+    let labels = derived_labels(app);
+    let values = derived_values(app, app.compare_model);
+    let reference = derived_values(app, REFERENCE);
+    rows(&labels, &values, &reference)
+}
+
+pub fn section(title: String) -> ListItem<'static> {
+    ListItem::new(title)
+        .fg(Color::White)
+        .bg(Color::Blue)
+}
+
+fn row_item(app: &App, row: &Row) -> ListItem<'static> {
+    let color = if row.percent_deviation().abs() > app.compare_threshold {
+        Color::LightRed
+    } else {
+        Color::LightCyan
+    };
+    ListItem::new(
+        format!("{:<18} {:>14.4} {:>14.4} {:>12.4} {:>9.4} {}",
+            row.label, row.value, row.reference, row.deviation(), row.percent_deviation(), row.unit,
+        )
+    )
+        .fg(color)
+        .bg(Color::Black)
+}
+
+pub fn set_param(app: &mut App, val: f64) {
+    if app.input_param == 1 {
+        app.compare_threshold = val.abs();
+    }
+}
+
+pub fn run_compare(app: &mut App) -> Vec<ListItem<'static>> {
+    let p_str = app.units.pressure.print_unit();
+    let t_str = app.units.temp.print_unit();
+    let mut items = vec![
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "1-Highlight Threshold:", app.compare_threshold, "%",
+            )
+        )
+            .fg(Color::LightYellow)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<18} {:>14} {:>14} {:>12} {:>9} {}",
+                "Property", app.compare_model.name(), REFERENCE.name(), "Deviation", "Dev (%)", "Unit",
+            )
+        )
+            .fg(Color::White)
+            .bg(Color::Black),
+    ];
+
+    let states = [
+        ("Current State", true, app.cur_state),
+        ("Inlet State", app.show_inlet_state, app.inlet_state),
+        ("Outlet State", app.show_outlet_state, app.outlet_state),
+    ];
+    let mut body = vec![];
+    for (title, shown, state) in states {
+        if !shown {
+            continue;
+        }
+        body.push(section(
+            format!("{} at {:.4} {}, {:.4} {}",
+                title,
+                units::get_pressure(state.p, app.units.pressure), p_str,
+                units::get_temperature(state.t, app.units.temp), t_str,
+            )
+        ));
+        for row in state_rows(app, &state) {
+            body.push(row_item(app, &row));
+        }
+    }
+
+    if app.show_inlet_state && app.show_outlet_state {
+        body.push(section("State Change and Isentropic Calculations".to_string()));
+        for row in derived_rows(app) {
+            body.push(row_item(app, &row));
+        }
+    }
+
+    app.compare_scroll = app.compare_scroll.min(body.len().saturating_sub(1));
+    items.extend(body.into_iter().skip(app.compare_scroll));
+    items
+}
@@ -0,0 +1,143 @@
+use ratatui::widgets::ListItem;
+use ratatui::
+    style::{
+        Color,
+        Stylize
+    };
+
+use crate::flash;
+use crate::units::{
+    self,
+    PrintUnit,
+};
+use crate::App;
+
+pub struct Factors {
+    pub fp: f64,
+    pub ft: f64,
+    pub fpv: f64,
+    pub z_line: f64,
+    pub z_base: f64,
+    // Total conversion factor from actual to base volume
+    pub total: f64,
+}
+
+// AGA 7 conversion factors from the current (line) state to base conditions
+pub fn conversion_factors(app: &App) -> Factors {
+    let (p, t, z_line) = (app.cur_state.p, app.cur_state.t, app.cur_state.z);
+    let z_base = flash::props_pt(&app.gas_comp, app.model, app.meter_base_p, app.meter_base_t).z;
+    let fp = p / app.meter_base_p;
+    let ft = app.meter_base_t / t;
+    let fpv = (z_base / z_line).sqrt();
+    Factors {
+        fp,
+        ft,
+        fpv,
+        z_line,
+        z_base,
+        total: fp * ft * fpv * fpv,
+    }
+}
+
+// Volume unit matching the selected flow unit system
+fn volume_unit(app: &App) -> &'static str {
+    if app.units.flow.is_imperial() { "ft^3" } else { "m^3" }
+}
+
+pub fn set_param(app: &mut App, val: f64) {
+    match app.input_param {
+        1 => app.meter_volume = val,
+        2 => app.meter_base_p = units::set_pressure(val, app.units.pressure),
+        3 => app.meter_base_t = units::set_temperature(val, app.units.temp),
+        _ => {}
+    }
+}
+
+pub fn run_corrector(app: &App) -> Vec<ListItem<'static>> {
+    let factors = conversion_factors(app);
+    let p_str = app.units.pressure.print_unit();
+    let t_str = app.units.temp.print_unit();
+    let vol_str = volume_unit(app);
+
+    vec![
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "1-Actual Volume:", app.meter_volume, vol_str,
+            )
+        )
+            .fg(Color::LightYellow)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "2-Base Pressure:", units::get_pressure(app.meter_base_p, app.units.pressure), p_str,
+            )
+        )
+            .fg(Color::LightYellow)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "3-Base Temperature:", units::get_temperature(app.meter_base_t, app.units.temp), t_str,
+            )
+        )
+            .fg(Color::LightYellow)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.6} {}",
+                "Line Z:", factors.z_line, "[]",
+            )
+        )
+            .fg(Color::White)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.6} {}",
+                "Base Z:", factors.z_base, "[]",
+            )
+        )
+            .fg(Color::White)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.6} {}",
+                "Pressure Factor Fp:", factors.fp, "[]",
+            )
+        )
+            .fg(Color::LightCyan)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.6} {}",
+                "Temperature Factor Ft:", factors.ft, "[]",
+            )
+        )
+            .fg(Color::LightCyan)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.6} {}",
+                "Supercompress. Fpv:", factors.fpv, "[]",
+            )
+        )
+            .fg(Color::LightCyan)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.6} {}",
+                "Conversion Factor:", factors.total, "[]",
+            )
+        )
+            .fg(Color::LightCyan)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Base Volume:", app.meter_volume * factors.total, vol_str,
+            )
+        )
+            .fg(Color::LightCyan)
+            .bg(Color::Black),
+    ]
+}
@@ -0,0 +1,256 @@
+use aga8::composition::Composition;
+use aga8::gerg2008::Gerg2008;
+
+use crate::eos::{
+    ideal_part,
+    Eos,
+    GasProps,
+    R,
+};
+use crate::gas::components;
+
+// Critical constants in the order of gas::components (Poling, Prausnitz &
+// O'Connell)
+// (Tc K, Pc kPa, acentric factor)
+pub const CRITICAL_DATA: [(f64, f64, f64); 21] = [
+    (190.56, 4599.0, 0.011),
+    (126.20, 3398.0, 0.037),
+    (304.12, 7374.0, 0.225),
+    (305.32, 4872.0, 0.099),
+    (369.83, 4248.0, 0.152),
+    (407.85, 3640.0, 0.186),
+    (425.12, 3796.0, 0.200),
+    (460.39, 3381.0, 0.229),
+    (469.70, 3370.0, 0.252),
+    (507.60, 3025.0, 0.300),
+    (540.20, 2740.0, 0.350),
+    (568.70, 2490.0, 0.399),
+    (594.60, 2290.0, 0.445),
+    (617.70, 2110.0, 0.490),
+    (33.19, 1313.0, -0.216),
+    (154.58, 5043.0, 0.022),
+    (132.85, 3494.0, 0.045),
+    (647.14, 22064.0, 0.344),
+    (373.40, 8963.0, 0.090),
+    (5.19, 227.0, -0.390),
+    (150.86, 4898.0, -0.002),
+];
+
+#[derive(Clone, Copy)]
+pub enum CubicKind {
+    PengRobinson,
+    Srk,
+}
+
+impl CubicKind {
+    // Denominator constants (delta1, delta2) and the a and b coefficients
+    fn constants(&self) -> (f64, f64, f64, f64) {
+        match self {
+            CubicKind::PengRobinson => (1.0 + 2.0_f64.sqrt(), 1.0 - 2.0_f64.sqrt(), 0.45724, 0.07780),
+            CubicKind::Srk => (1.0, 0.0, 0.42748, 0.08664),
+        }
+    }
+
+    // Slope of the alpha function against the acentric factor
+    fn m(&self, omega: f64) -> f64 {
+        match self {
+            CubicKind::PengRobinson => 0.37464 + 1.54226 * omega - 0.26992 * omega * omega,
+            CubicKind::Srk => 0.480 + 1.574 * omega - 0.176 * omega * omega,
+        }
+    }
+}
+
+// Pure component parameters: mole fraction, Tc, sqrt(ac), m and b
+struct Component {
+    x: f64,
+    tc: f64,
+    sqrt_ac: f64,
+    m: f64,
+    b: f64,
+}
+
+// Two-parameter cubic equation of state with van der Waals one-fluid mixing
+// (kij = 0); the ideal gas part comes from GERG-2008 so h and s share its
+// reference state
+pub struct Cubic {
+    kind: CubicKind,
+    gerg: Gerg2008,
+    comps: Vec<Component>,
+    b: f64,
+}
+
+impl Cubic {
+    pub fn new(kind: CubicKind) -> Self {
+        Cubic {
+            kind,
+            gerg: Gerg2008::new(),
+            comps: Vec::new(),
+            b: 0.0,
+        }
+    }
+
+    // Mixture a and its first and second temperature derivatives
+    fn attraction(&self, t: f64) -> (f64, f64, f64) {
+        let mut a0 = 0.0;
+        let mut a1 = 0.0;
+        let mut a2 = 0.0;
+        for c in &self.comps {
+            let root = (t * c.tc).sqrt();
+            a0 += c.x * c.sqrt_ac * (1.0 + c.m * (1.0 - (t / c.tc).sqrt()));
+            a1 -= c.x * c.sqrt_ac * c.m / (2.0 * root);
+            a2 += c.x * c.sqrt_ac * c.m / (4.0 * t * root);
+        }
+        (a0 * a0, 2.0 * a0 * a1, 2.0 * (a1 * a1 + a0 * a2))
+    }
+}
+
+// Largest real root of z^3 + c2 z^2 + c1 z + c0 = 0
+fn largest_root(c2: f64, c1: f64, c0: f64) -> f64 {
+    let e = c1 - c2 * c2 / 3.0;
+    let f = 2.0 * c2.powi(3) / 27.0 - c2 * c1 / 3.0 + c0;
+    let disc = (f / 2.0).powi(2) + (e / 3.0).powi(3);
+    let y = if disc > 0.0 {
+        (-f / 2.0 + disc.sqrt()).cbrt() + (-f / 2.0 - disc.sqrt()).cbrt()
+    } else {
+        let r = (-e / 3.0).sqrt();
+        let phi = (-f / 2.0 / r.powi(3)).clamp(-1.0, 1.0).acos();
+        2.0 * r * (phi / 3.0).cos()
+    };
+    y - c2 / 3.0
+}
+
+impl Eos for Cubic {
+    fn set_composition(&mut self, gas_comp: &Composition) {
+        let _ = self.gerg.set_composition(gas_comp);
+        let (_, _, omega_a, omega_b) = self.kind.constants();
+        self.comps = components(gas_comp)
+            .iter()
+            .zip(CRITICAL_DATA.iter())
+            .filter(|((_, x), _)| *x > 0.0)
+            .map(|((_, x), (tc, pc, omega))| Component {
+                x: *x,
+                tc: *tc,
+                sqrt_ac: (omega_a * R * R * tc * tc / pc).sqrt(),
+                m: self.kind.m(*omega),
+                b: omega_b * R * tc / pc,
+            })
+            .collect();
+        self.b = self.comps.iter().map(|c| c.x * c.b).sum();
+    }
+
+    fn props_pt(&mut self, p: f64, t: f64) -> GasProps {
+        let (delta1, delta2, _, _) = self.kind.constants();
+        let (u, w) = (delta1 + delta2, delta1 * delta2);
+        let (a, _, _) = self.attraction(t);
+        let aa = a * p / (R * t).powi(2);
+        let bb = self.b * p / (R * t);
+        let z = largest_root(
+            -(1.0 + bb - u * bb),
+            aa + w * bb * bb - u * bb - u * bb * bb,
+            -(aa * bb + w * bb * bb + w * bb.powi(3)),
+        );
+        self.props_td(t, p / (z * R * t))
+    }
+
+    fn props_td(&mut self, t: f64, d: f64) -> GasProps {
+        let (delta1, delta2, _, _) = self.kind.constants();
+        let (a, da, d2a) = self.attraction(t);
+        let b = self.b;
+        let v = 1.0 / d;
+        let e1 = v + delta1 * b;
+        let e2 = v + delta2 * b;
+        let integral = (e1 / e2).ln() / (b * (delta1 - delta2));
+
+        let p = R * t / (v - b) - a / (e1 * e2);
+        let dp_dt = R / (v - b) - da / (e1 * e2);
+        let dp_dv = -R * t / (v - b).powi(2) + a * (e1 + e2) / (e1 * e2).powi(2);
+
+        let ideal = ideal_part(&mut self.gerg, t, d);
+        let u = ideal.h - R * t + (t * da - a) * integral;
+        let h = u + p * v;
+        let s = ideal.s + R * ((v - b) / v).ln() + da * integral;
+        let cv = ideal.cp - R + t * d2a * integral;
+        let cp = cv - t * dp_dt * dp_dt / dp_dv;
+        let z = p * v / (R * t);
+        let w = (1000.0 * cp / cv * -v * v * dp_dv / ideal.mm).max(0.0).sqrt();
+        GasProps {
+            p,
+            t,
+            mm: ideal.mm,
+            d,
+            z,
+            dp_dt,
+            dp_dd: -v * v * dp_dv,
+            u,
+            h,
+            s,
+            cp,
+            cv,
+            w,
+            jt: (-t * dp_dt / dp_dv - v) / cp,
+            kappa: w * w * ideal.mm / (R * t * 1000.0 * z),
+            g: h - t * s,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eos::{
+        new_eos,
+        Model,
+    };
+    use crate::gas::pure_component;
+
+    #[test]
+    fn largest_root_of_three_and_one_real_roots() {
+        // (z - 1)(z - 2)(z - 3)
+        assert!((largest_root(-6.0, 11.0, -6.0) - 3.0).abs() < 1.0e-12);
+        // (z - 1)(z^2 + z + 2)
+        assert!((largest_root(0.0, 1.0, -2.0) - 1.0).abs() < 1.0e-12);
+    }
+
+    // Methane at 300 K and 5 MPa, Z = 0.9196 (NIST, Setzmann and Wagner);
+    // PR is known to overpredict the density of methane by about 2%
+    #[test]
+    fn methane_compressibility() {
+        let methane = pure_component(0);
+        let pr = new_eos(Model::PengRobinson, &methane).props_pt(5000.0, 300.0);
+        let srk = new_eos(Model::Srk, &methane).props_pt(5000.0, 300.0);
+        assert!((pr.z / 0.9196 - 1.0).abs() < 0.025, "PR Z {}", pr.z);
+        assert!((srk.z / 0.9196 - 1.0).abs() < 0.01, "SRK Z {}", srk.z);
+    }
+
+    // Analytic derivatives against central differences of h at constant p,
+    // u at constant d and p at constant d
+    #[test]
+    fn derivatives_match_differences() {
+        let methane = pure_component(0);
+        for model in [Model::PengRobinson, Model::Srk] {
+            let mut eos = new_eos(model, &methane);
+            let (p, t, dt) = (8000.0, 250.0, 1.0e-3);
+            let props = eos.props_pt(p, t);
+            let cp = (eos.props_pt(p, t + dt).h - eos.props_pt(p, t - dt).h) / (2.0 * dt);
+            let cv = (eos.props_td(t + dt, props.d).u - eos.props_td(t - dt, props.d).u) / (2.0 * dt);
+            let dp_dt = (eos.props_td(t + dt, props.d).p - eos.props_td(t - dt, props.d).p) / (2.0 * dt);
+            assert!((props.cp / cp - 1.0).abs() < 1.0e-5, "cp {} {}", props.cp, cp);
+            assert!((props.cv / cv - 1.0).abs() < 1.0e-5, "cv {} {}", props.cv, cv);
+            assert!((props.dp_dt / dp_dt - 1.0).abs() < 1.0e-6, "dp/dT {} {}", props.dp_dt, dp_dt);
+        }
+    }
+
+    // The residual parts vanish at zero pressure, leaving the GERG-2008
+    // ideal gas enthalpy and entropy
+    #[test]
+    fn ideal_gas_limit() {
+        let methane = pure_component(0);
+        let ideal = new_eos(Model::IdealGas, &methane).props_pt(1.0e-3, 300.0);
+        for model in [Model::PengRobinson, Model::Srk] {
+            let props = new_eos(model, &methane).props_pt(1.0e-3, 300.0);
+            assert!((props.z - 1.0).abs() < 1.0e-6);
+            assert!((props.h - ideal.h).abs() < 1.0e-3);
+            assert!((props.s - ideal.s).abs() < 1.0e-4);
+        }
+    }
+}
@@ -0,0 +1,195 @@
+use ratatui::widgets::ListItem;
+use ratatui::
+    style::{
+        Color,
+        Stylize
+    };
+
+use crate::compare::{
+    self,
+    section,
+};
+use crate::eos::Model;
+use crate::flash::{
+    self,
+    GasProps,
+};
+use crate::units::{
+    self,
+    PrintUnit,
+};
+use crate::App;
+
+// Real gas value against the ideal gas value at the same T, P and
+// composition
+pub struct Departure {
+    pub label: &'static str,
+    pub real: f64,
+    pub ideal: f64,
+    pub unit: &'static str,
+}
+
+impl Departure {
+    // Residual (departure) value, real minus ideal
+    pub fn residual(&self) -> f64 {
+        self.real - self.ideal
+    }
+
+    // Error of the ideal gas value relative to the real gas value
+    pub fn ideal_error(&self) -> f64 {
+        if self.real == 0.0 {
+            return 0.0
+        }
+        (self.ideal - self.real) / self.real.abs() * 100.0
+    }
+}
+
+// Ideal gas reference properties (cp0, h0, s0, g0) for a state
+pub fn ideal_state(app: &App, state: &GasProps) -> GasProps {
+    flash::props_pt(&app.gas_comp, Model::IdealGas, state.p, state.t)
+}
+
+pub fn departures(app: &App, state: &GasProps) -> Vec<Departure> {
+    let ideal = app.ref_offset.apply(&ideal_state(app, state));
+    let state = &app.ref_offset.apply(state);
+    let mm = state.mm;
+    let energy_str = app.units.energy.print_unit();
+    let entropy_str = app.units.entropy.print_unit();
+    vec![
+        Departure {
+            label: "Z:",
+            real: state.z,
+            ideal: ideal.z,
+            unit: "[]",
+        },
+        Departure {
+            label: "Cp:",
+            real: units::get_entropy(state.cp, app.units.entropy, mm),
+            ideal: units::get_entropy(ideal.cp, app.units.entropy, mm),
+            unit: entropy_str,
+        },
+        Departure {
+            label: "Enthalpy:",
+            real: units::get_energy(state.h, app.units.energy, mm),
+            ideal: units::get_energy(ideal.h, app.units.energy, mm),
+            unit: energy_str,
+        },
+        Departure {
+            label: "Entropy:",
+            real: units::get_entropy(state.s, app.units.entropy, mm),
+            ideal: units::get_entropy(ideal.s, app.units.entropy, mm),
+            unit: entropy_str,
+        },
+        Departure {
+            label: "Gibbs Energy:",
+            real: units::get_energy(state.g, app.units.energy, mm),
+            ideal: units::get_energy(ideal.g, app.units.energy, mm),
+            unit: energy_str,
+        },
+    ]
+}
+
+// Compressor calculations with the active model and with the ideal gas
+// model at the same inlet and outlet P and T
+pub fn compressor_departures(app: &mut App) -> Vec<Departure> {
+    let labels = compare::derived_labels(app);
+    let real = compare::derived_values(app, app.model);
+    let ideal = compare::derived_values(app, Model::IdealGas);
+    labels
+        .iter()
+        .zip(real.iter().zip(ideal.iter()))
+        .map(|((label, unit), (real, ideal))| Departure {
+            label,
+            real: *real,
+            ideal: *ideal,
+            unit,
+        })
+        .collect()
+}
+
+pub fn run_departure(app: &mut App) -> Vec<ListItem<'static>> {
+    let p_str = app.units.pressure.print_unit();
+    let t_str = app.units.temp.print_unit();
+    let mut items = vec![
+        ListItem::new(
+            format!("{:<24} {}",
+                "Real Gas Model:", app.model.name(),
+            )
+        )
+            .fg(Color::White)
+            .bg(Color::Black),
+    ];
+
+    let states = [
+        ("Current State", true, app.cur_state),
+        ("Inlet State", app.show_inlet_state, app.inlet_state),
+        ("Outlet State", app.show_outlet_state, app.outlet_state),
+    ];
+    let mut body = vec![];
+    for (title, shown, state) in states {
+        if !shown {
+            continue;
+        }
+        body.push(section(
+            format!("{} at {:.4} {}, {:.4} {}",
+                title,
+                units::get_pressure(state.p, app.units.pressure), p_str,
+                units::get_temperature(state.t, app.units.temp), t_str,
+            )
+        ));
+        body.push(
+            ListItem::new(
+                format!("{:<18} {:>14} {:>14} {:>14} {}",
+                    "Property", "Real Gas", "Ideal Gas", "Departure", "Unit",
+                )
+            )
+                .fg(Color::White)
+                .bg(Color::Black)
+        );
+        for row in departures(app, &state) {
+            body.push(
+                ListItem::new(
+                    format!("{:<18} {:>14.4} {:>14.4} {:>14.4} {}",
+                        row.label, row.real, row.ideal, row.residual(), row.unit,
+                    )
+                )
+                    .fg(Color::LightCyan)
+                    .bg(Color::Black)
+            );
+        }
+    }
+
+    if app.show_inlet_state && app.show_outlet_state {
+        body.push(section("Ideal Gas Error in Compressor Calculations".to_string()));
+        body.push(
+            ListItem::new(
+                format!("{:<18} {:>14} {:>14} {:>14} {}",
+                    "Property", "Real Gas", "Ideal Gas", "Error (%)", "Unit",
+                )
+            )
+                .fg(Color::White)
+                .bg(Color::Black)
+        );
+        for row in compressor_departures(app) {
+            body.push(
+                ListItem::new(
+                    format!("{:<18} {:>14.4} {:>14.4} {:>14.4} {}",
+                        row.label, row.real, row.ideal, row.ideal_error(), row.unit,
+                    )
+                )
+                    .fg(Color::LightCyan)
+                    .bg(Color::Black)
+            );
+        }
+    } else {
+        body.push(
+            ListItem::new("Set the inlet (I) and outlet (O) states for the compressor error")
+                .fg(Color::White)
+                .bg(Color::Black)
+        );
+    }
+
+    app.departure_scroll = app.departure_scroll.min(body.len().saturating_sub(1));
+    items.extend(body.into_iter().skip(app.departure_scroll));
+    items
+}
@@ -0,0 +1,350 @@
+use aga8::composition::Composition;
+use aga8::detail::Detail;
+use aga8::gerg2008::Gerg2008;
+
+use crate::cubic::{
+    Cubic,
+    CubicKind,
+};
+
+// Gas constant in J/(mol-K)
+pub const R: f64 = 8.314462618;
+// Density (mol/l) at which the GERG-2008 residual terms vanish, used to take
+// the ideal gas part for the models without their own
+const IDEAL_DENSITY: f64 = 1.0e-6;
+// Density (mol/l) for the finite difference second virial coefficient
+const VIRIAL_DENSITY: f64 = 1.0e-4;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Model {
+    Detail,
+    Gerg2008,
+    PengRobinson,
+    Srk,
+    IdealGas,
+}
+
+impl Model {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Model::Detail => "AGA8",
+            Model::Gerg2008 => "GERG-2008",
+            Model::PengRobinson => "Peng-Robinson",
+            Model::Srk => "SRK",
+            Model::IdealGas => "Ideal Gas",
+        }
+    }
+
+    pub fn next(&self) -> Model {
+        match self {
+            Model::Detail => Model::Gerg2008,
+            Model::Gerg2008 => Model::PengRobinson,
+            Model::PengRobinson => Model::Srk,
+            Model::Srk => Model::IdealGas,
+            Model::IdealGas => Model::Detail,
+        }
+    }
+}
+
+// Property set for a single point, independent of the equation of state used
+#[derive(Clone, Copy, Default)]
+pub struct GasProps {
+    pub p: f64,
+    pub t: f64,
+    pub mm: f64,
+    pub d: f64,
+    pub z: f64,
+    // dP/dT at constant density (kPa/K) and dP/dD at constant temperature
+    // (kPa/(mol/l))
+    pub dp_dt: f64,
+    pub dp_dd: f64,
+    pub u: f64,
+    pub h: f64,
+    pub s: f64,
+    pub cp: f64,
+    pub cv: f64,
+    pub w: f64,
+    pub jt: f64,
+    pub kappa: f64,
+    pub g: f64,
+}
+
+impl GasProps {
+    // Isothermal compressibility in 1/kPa
+    pub fn isothermal_compressibility(&self) -> f64 {
+        1.0 / (self.d * self.dp_dd)
+    }
+
+    // Volume expansivity in 1/K
+    pub fn volume_expansivity(&self) -> f64 {
+        self.dp_dt / (self.d * self.dp_dd)
+    }
+
+    fn from_gerg(gas_state: &Gerg2008) -> Self {
+        GasProps {
+            p: gas_state.p,
+            t: gas_state.t,
+            mm: gas_state.mm,
+            d: gas_state.d,
+            z: gas_state.z,
+            dp_dt: gas_state.dp_dt,
+            dp_dd: gas_state.dp_dd,
+            u: gas_state.u,
+            h: gas_state.h,
+            s: gas_state.s,
+            cp: gas_state.cp,
+            cv: gas_state.cv,
+            w: gas_state.w,
+            jt: gas_state.jt,
+            kappa: gas_state.kappa,
+            g: gas_state.g,
+        }
+    }
+
+    fn from_detail(gas_state: &Detail) -> Self {
+        GasProps {
+            p: gas_state.p,
+            t: gas_state.t,
+            mm: gas_state.mm,
+            d: gas_state.d,
+            z: gas_state.z,
+            dp_dt: gas_state.dp_dt,
+            dp_dd: gas_state.dp_dd,
+            u: gas_state.u,
+            h: gas_state.h,
+            s: gas_state.s,
+            cp: gas_state.cp,
+            cv: gas_state.cv,
+            w: gas_state.w,
+            jt: gas_state.jt,
+            kappa: gas_state.kappa,
+            g: gas_state.g,
+        }
+    }
+}
+
+// Equation of state backend; pressure in kPa, temperature in K, molar
+// density in mol/l and molar properties in J/mol
+pub trait Eos {
+    fn set_composition(&mut self, gas_comp: &Composition);
+    fn props_pt(&mut self, p: f64, t: f64) -> GasProps;
+    fn props_td(&mut self, t: f64, d: f64) -> GasProps;
+}
+
+impl Eos for Detail {
+    fn set_composition(&mut self, gas_comp: &Composition) {
+        let _ = Detail::set_composition(self, gas_comp);
+    }
+
+    fn props_pt(&mut self, p: f64, t: f64) -> GasProps {
+        self.p = p;
+        self.t = t;
+        let _ = self.density();
+        self.properties();
+        GasProps::from_detail(self)
+    }
+
+    fn props_td(&mut self, t: f64, d: f64) -> GasProps {
+        self.t = t;
+        self.d = d;
+        self.p = self.pressure();
+        self.properties();
+        GasProps::from_detail(self)
+    }
+}
+
+impl Eos for Gerg2008 {
+    fn set_composition(&mut self, gas_comp: &Composition) {
+        let _ = Gerg2008::set_composition(self, gas_comp);
+    }
+
+    fn props_pt(&mut self, p: f64, t: f64) -> GasProps {
+        self.p = p;
+        self.t = t;
+        let _ = self.density(0);
+        self.properties();
+        GasProps::from_gerg(self)
+    }
+
+    fn props_td(&mut self, t: f64, d: f64) -> GasProps {
+        self.t = t;
+        self.d = d;
+        self.p = self.pressure();
+        self.properties();
+        GasProps::from_gerg(self)
+    }
+}
+
+// Ideal gas part at temperature t and molar density d: enthalpy (J/mol),
+// entropy (J/(mol-K)), cp (J/(mol-K)) and molar mass (g/mol), on the
+// GERG-2008 reference state
+pub struct IdealPart {
+    pub h: f64,
+    pub s: f64,
+    pub cp: f64,
+    pub mm: f64,
+}
+
+pub fn ideal_part(gerg: &mut Gerg2008, t: f64, d: f64) -> IdealPart {
+    gerg.t = t;
+    gerg.d = IDEAL_DENSITY;
+    gerg.p = gerg.pressure();
+    gerg.properties();
+    IdealPart {
+        h: gerg.h,
+        s: gerg.s - R * (d / IDEAL_DENSITY).ln(),
+        cp: gerg.cp,
+        mm: gerg.mm,
+    }
+}
+
+pub struct IdealGas {
+    gerg: Gerg2008,
+}
+
+impl IdealGas {
+    pub fn new() -> Self {
+        IdealGas {
+            gerg: Gerg2008::new(),
+        }
+    }
+}
+
+impl Eos for IdealGas {
+    fn set_composition(&mut self, gas_comp: &Composition) {
+        let _ = self.gerg.set_composition(gas_comp);
+    }
+
+    fn props_pt(&mut self, p: f64, t: f64) -> GasProps {
+        self.props_td(t, p / (R * t))
+    }
+
+    fn props_td(&mut self, t: f64, d: f64) -> GasProps {
+        let ideal = ideal_part(&mut self.gerg, t, d);
+        let rt = R * t;
+        let cv = ideal.cp - R;
+        let kappa = ideal.cp / cv;
+        GasProps {
+            p: d * rt,
+            t,
+            mm: ideal.mm,
+            d,
+            z: 1.0,
+            dp_dt: d * R,
+            dp_dd: rt,
+            u: ideal.h - rt,
+            h: ideal.h,
+            s: ideal.s,
+            cp: ideal.cp,
+            cv,
+            w: (1000.0 * kappa * rt / ideal.mm).sqrt(),
+            jt: 0.0,
+            kappa,
+            g: ideal.h - t * ideal.s,
+        }
+    }
+}
+
+pub fn new_eos(model: Model, gas_comp: &Composition) -> Box<dyn Eos> {
+    let mut eos: Box<dyn Eos> = match model {
+        Model::Detail => Box::new(Detail::new()),
+        Model::Gerg2008 => Box::new(Gerg2008::new()),
+        Model::PengRobinson => Box::new(Cubic::new(CubicKind::PengRobinson)),
+        Model::Srk => Box::new(Cubic::new(CubicKind::Srk)),
+        Model::IdealGas => Box::new(IdealGas::new()),
+    };
+    eos.set_composition(gas_comp);
+    eos
+}
+
+// Second virial coefficient (l/mol) at temperature t from the slope of Z at
+// low density
+pub fn second_virial(gas_comp: &Composition, model: Model, t: f64) -> f64 {
+    let mut eos = new_eos(model, gas_comp);
+    (eos.props_td(t, VIRIAL_DENSITY).z - 1.0) / VIRIAL_DENSITY
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::gas::{
+        get_gas_comp,
+        Gas,
+    };
+
+    // Demonstration gas of the AGA8 Part 1 and GERG-2008 reference programs
+    pub(crate) const DEMO_COMP: Composition = Composition {
+        methane: 0.77824,
+        nitrogen: 0.02,
+        carbon_dioxide: 0.06,
+        ethane: 0.08,
+        propane: 0.03,
+        isobutane: 0.0015,
+        n_butane: 0.003,
+        isopentane: 0.0005,
+        n_pentane: 0.00165,
+        hexane: 0.00215,
+        heptane: 0.00088,
+        octane: 0.00024,
+        nonane: 0.00015,
+        decane: 0.00009,
+        hydrogen: 0.004,
+        oxygen: 0.005,
+        carbon_monoxide: 0.002,
+        water: 0.0001,
+        hydrogen_sulfide: 0.0025,
+        helium: 0.007,
+        argon: 0.001,
+    };
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!((actual - expected).abs() <= tolerance, "{} differs from {} by more than {}", actual, expected, tolerance);
+    }
+
+    // Reference program output at 400 K and 50 MPa
+    #[test]
+    fn detail_demo_state() {
+        let props = new_eos(Model::Detail, &DEMO_COMP).props_pt(50000.0, 400.0);
+        assert_close(props.d, 12.807924036488, 1.0e-9);
+        assert_close(props.z, 1.173801364147, 1.0e-9);
+        assert_close(props.h, 1164.699096269, 1.0e-6);
+        assert_close(props.s, -38.548826846771, 1.0e-9);
+        assert_close(props.w, 712.639368405790, 1.0e-6);
+    }
+
+    #[test]
+    fn gerg_demo_state() {
+        let props = new_eos(Model::Gerg2008, &DEMO_COMP).props_pt(50000.0, 400.0);
+        assert_close(props.d, 12.798286260821, 1.0e-9);
+        assert_close(props.z, 1.174690666384, 1.0e-9);
+        assert_close(props.h, 1160.280160511, 1.0e-6);
+        assert_close(props.s, -38.575903924091, 1.0e-9);
+        assert_close(props.w, 714.424884059602, 1.0e-6);
+    }
+
+    #[test]
+    fn gerg_state_from_density() {
+        let props = new_eos(Model::Gerg2008, &DEMO_COMP).props_td(400.0, 12.798286260821);
+        assert_close(props.p, 50000.0, 1.0e-4);
+        assert_close(props.u, -2746.492901213, 1.0e-6);
+    }
+
+    // Ideal gas cp/cv and zero pressure limit of the real gas models
+    #[test]
+    fn ideal_gas_limit() {
+        let mut ideal_gas = new_eos(Model::IdealGas, &DEMO_COMP);
+        let mut gerg = new_eos(Model::Gerg2008, &DEMO_COMP);
+        let ideal = ideal_gas.props_pt(1.0e-3, 300.0);
+        let real = gerg.props_pt(1.0e-3, 300.0);
+        assert_close(ideal.cp - ideal.cv, R, 1.0e-9);
+        assert_close(real.h, ideal.h, 1.0e-3);
+        assert_close(real.s, ideal.s, 1.0e-4);
+    }
+
+    // Air at 300 K, B = -7.3 cm^3/mol (Dymond and Smith)
+    #[test]
+    fn air_second_virial() {
+        let air = get_gas_comp(Gas::Air);
+        assert_close(second_virial(&air, Model::Gerg2008, 300.0), -0.0073, 0.0005);
+    }
+}
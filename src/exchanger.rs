@@ -0,0 +1,263 @@
+use ratatui::widgets::ListItem;
+use ratatui::
+    style::{
+        Color,
+        Stylize
+    };
+
+use crate::units::{
+    self,
+    Flow,
+    PrintUnit,
+};
+use crate::App;
+
+// Critical point and molar mass of water
+const WATER_TC: f64 = 647.096;
+const WATER_PC: f64 = 22064.0;
+const WATER_MM: f64 = 18.015268;
+
+// Water vapor pressure (kPa) from the Wagner-Pruss saturation equation
+pub fn water_vapor_pressure(t: f64) -> f64 {
+    if t >= WATER_TC {
+        return WATER_PC
+    }
+    let tau = 1.0 - t / WATER_TC;
+    let ln_pr = WATER_TC / t * (
+        -7.85951783 * tau
+        + 1.84408259 * tau.powf(1.5)
+        - 11.7866497 * tau.powi(3)
+        + 22.6807411 * tau.powf(3.5)
+        - 15.9618719 * tau.powi(4)
+        + 1.80122502 * tau.powf(7.5)
+    );
+    WATER_PC * ln_pr.exp()
+}
+
+// Water dew point (K) for the water mole fraction at pressure p (kPa)
+pub fn water_dew_point(water: f64, p: f64) -> f64 {
+    let p_w = water * p;
+    if p_w <= 0.0 {
+        return 0.0
+    }
+    let mut t_low = 150.0;
+    let mut t_high = WATER_TC;
+    for _ in 0..100 {
+        let t_mid = (t_low + t_high) / 2.0;
+        if water_vapor_pressure(t_mid) > p_w {
+            t_high = t_mid;
+        } else {
+            t_low = t_mid;
+        }
+    }
+    (t_low + t_high) / 2.0
+}
+
+// Latent heat of water (kJ/kg) from the Watson correlation
+pub fn water_latent_heat(t: f64) -> f64 {
+    if t >= WATER_TC {
+        return 0.0
+    }
+    2257.0 * ((WATER_TC - t) / (WATER_TC - 373.15)).powf(0.38)
+}
+
+// Water condensed (kg/s) when the stream is cooled to t2 at pressure p2
+pub fn condensed_water(app: &App, mm: f64, p2: f64, t2: f64) -> f64 {
+    let y_in = app.gas_comp.water;
+    let y_sat = water_vapor_pressure(t2) / p2;
+    if y_in <= y_sat || y_sat >= 1.0 {
+        return 0.0
+    }
+    // kmol of water condensed per kmol of feed
+    let n_cond = (y_in - y_sat) / (1.0 - y_sat);
+    app.flow_val / mm * n_cond * WATER_MM
+}
+
+// Log mean temperature difference for counter-current flow, none when the
+// temperatures cross
+pub fn lmtd(t_hot_in: f64, t_hot_out: f64, t_cold_in: f64, t_cold_out: f64) -> Option<f64> {
+    let dt1 = t_hot_in - t_cold_out;
+    let dt2 = t_hot_out - t_cold_in;
+    if dt1 <= 0.0 || dt2 <= 0.0 {
+        return None
+    }
+    if (dt1 - dt2).abs() < 1.0e-9 {
+        return Some(dt1)
+    }
+    Some((dt1 - dt2) / (dt1 / dt2).ln())
+}
+
+pub fn set_param(app: &mut App, val: f64) {
+    match app.input_param {
+        1 => app.hx_utility_t_in = units::set_temperature(val, app.units.temp),
+        2 => app.hx_utility_t_out = units::set_temperature(val, app.units.temp),
+        _ => {}
+    }
+}
+
+pub fn run_exchanger(app: &App) -> Vec<ListItem<'static>> {
+    let t_str = app.units.temp.print_unit();
+    let power_str = app.units.power.print_unit();
+    let mut items = vec![
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "1-Utility Inlet Temp:", units::get_temperature(app.hx_utility_t_in, app.units.temp), t_str,
+            )
+        )
+            .fg(Color::LightYellow)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "2-Utility Outlet Temp:", units::get_temperature(app.hx_utility_t_out, app.units.temp), t_str,
+            )
+        )
+            .fg(Color::LightYellow)
+            .bg(Color::Black),
+    ];
+
+    if !(app.show_inlet_state && app.show_outlet_state) {
+        items.push(
+            ListItem::new("Set the inlet (I) and outlet (O) states of the stream")
+                .fg(Color::Red)
+                .bg(Color::Black)
+        );
+        return items
+    }
+
+    let (h1, h2, t1, t2, p2, mm) = (
+        app.inlet_state.h,
+        app.outlet_state.h,
+        app.inlet_state.t,
+        app.outlet_state.t,
+        app.outlet_state.p,
+        app.inlet_state.mm,
+    );
+
+    // Duties in kW, negative when heat is removed from the stream
+    let q_sensible = (h2 - h1) / mm * app.flow_val;
+    let water_kg_s = condensed_water(app, mm, p2, t2);
+    let q_latent = -water_kg_s * water_latent_heat(t2);
+    let q_total = q_sensible + q_latent;
+    let sensible_split = if q_total != 0.0 { q_sensible / q_total } else { 1.0 };
+
+    let (water_flow, water_str) = match app.units.flow {
+        Flow::Nm3_h | Flow::scfm | Flow::scfh => (water_kg_s * 3600.0, "kg/hr"),
+        unit => (
+            units::get_flow(water_kg_s, unit, &app.gas_comp, app.stp_60_F, app.model),
+            unit.print_unit(),
+        ),
+    };
+
+    items.push(
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Total Duty:", units::get_power(q_total, app.units.power), power_str,
+            )
+        )
+            .fg(Color::LightCyan)
+            .bg(Color::Black)
+    );
+    items.push(
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Sensible Duty:", units::get_power(q_sensible, app.units.power), power_str,
+            )
+        )
+            .fg(Color::LightCyan)
+            .bg(Color::Black)
+    );
+    items.push(
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Latent Duty:", units::get_power(q_latent, app.units.power), power_str,
+            )
+        )
+            .fg(Color::LightCyan)
+            .bg(Color::Black)
+    );
+    items.push(
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Sensible Heat Ratio:", sensible_split, "[]",
+            )
+        )
+            .fg(Color::LightCyan)
+            .bg(Color::Black)
+    );
+    items.push(
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Water Dew Point:", units::get_temperature(water_dew_point(app.gas_comp.water, p2), app.units.temp), t_str,
+            )
+        )
+            .fg(Color::LightCyan)
+            .bg(Color::Black)
+    );
+    items.push(
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Condensed Water:", water_flow, water_str,
+            )
+        )
+            .fg(Color::LightCyan)
+            .bg(Color::Black)
+    );
+
+    if app.hx_utility_t_in <= 0.0 || app.hx_utility_t_out <= 0.0 {
+        return items
+    }
+
+    // The process stream is the hot side when heat is removed from it and
+    // the utility is the hot side for a heater
+    let cooler = q_total < 0.0;
+    let (th_in, th_out, tc_in, tc_out) = if cooler {
+        (t1, t2, app.hx_utility_t_in, app.hx_utility_t_out)
+    } else {
+        (app.hx_utility_t_in, app.hx_utility_t_out, t1, t2)
+    };
+    // An isothermal (condensing or boiling) utility suits either duty
+    let utility_change = app.hx_utility_t_out - app.hx_utility_t_in;
+    if (cooler && utility_change < 0.0) || (!cooler && utility_change > 0.0) {
+        let msg = if cooler {
+            "Cooling duty: the utility must warm up from inlet to outlet"
+        } else {
+            "Heating duty: the utility must cool down from inlet to outlet"
+        };
+        items.push(
+            ListItem::new(msg)
+                .fg(Color::Red)
+                .bg(Color::Black)
+        );
+        return items
+    }
+    let Some(lmtd_k) = lmtd(th_in, th_out, tc_in, tc_out) else {
+        items.push(
+            ListItem::new("Temperature cross: the hot side must stay above the cold side at both ends")
+                .fg(Color::Red)
+                .bg(Color::Black)
+        );
+        return items
+    };
+    let lmtd_user = units::get_temperature_difference(lmtd_k, app.units.temp);
+
+    items.push(
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "LMTD:", lmtd_user, t_str,
+            )
+        )
+            .fg(Color::LightCyan)
+            .bg(Color::Black)
+    );
+    items.push(
+        ListItem::new(
+            format!("{:<24} {:.4} {}/{}",
+                "UA:", units::get_power(q_total.abs(), app.units.power) / lmtd_user, power_str, t_str,
+            )
+        )
+            .fg(Color::LightCyan)
+            .bg(Color::Black)
+    );
+    items
+}
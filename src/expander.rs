@@ -0,0 +1,195 @@
+use ratatui::widgets::ListItem;
+use ratatui::
+    style::{
+        Color,
+        Stylize
+    };
+
+use crate::calculations;
+use crate::flash::{
+    self,
+    GasProps,
+};
+use crate::units::{
+    self,
+    PrintUnit,
+};
+use crate::App;
+
+pub struct ExpanderResult {
+    pub inlet: GasProps,
+    pub outlet_s: GasProps,
+    pub outlet: GasProps,
+}
+
+// Expander inlet is the current state, leaving inlet/outlet for the brake compressor
+pub fn expand(app: &App) -> ExpanderResult {
+    let (p1, t1) = (app.cur_state.p, app.cur_state.t);
+    let inlet = flash::props_pt(&app.gas_comp, app.model, p1, t1);
+    let outlet_s = flash::props_ps(&app.gas_comp, app.model, app.expander_p2, inlet.s, inlet.t);
+    let h2 = inlet.h - app.expander_eff * (inlet.h - outlet_s.h);
+    let outlet = flash::props_ph(&app.gas_comp, app.model, app.expander_p2, h2, outlet_s.t);
+    ExpanderResult {
+        inlet,
+        outlet_s,
+        outlet,
+    }
+}
+
+// Recovered shaft power in kW
+pub fn recovered_power(app: &App, result: &ExpanderResult) -> f64 {
+    (result.inlet.h - result.outlet.h) / result.inlet.mm * app.flow_val
+}
+
+pub fn set_param(app: &mut App, val: f64) {
+    match app.input_param {
+        1 => app.expander_p2 = units::set_pressure(val, app.units.pressure),
+        2 => app.expander_eff = val,
+        _ => {}
+    }
+}
+
+pub fn run_expander(app: &mut App) -> Vec<ListItem<'static>> {
+    let result = expand(app);
+    let power = recovered_power(app, &result);
+    let mm = result.inlet.mm;
+    let p_str = app.units.pressure.print_unit();
+    let t_str = app.units.temp.print_unit();
+    let energy_str = app.units.energy.print_unit();
+    let power_str = app.units.power.print_unit();
+
+    let mut items = vec![
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "1-Outlet Pressure:", units::get_pressure(app.expander_p2, app.units.pressure), p_str,
+            )
+        )
+            .fg(Color::LightYellow)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "2-Isentropic Eff:", app.expander_eff, "[]",
+            )
+        )
+            .fg(Color::LightYellow)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Inlet Temp:", units::get_temperature(result.inlet.t, app.units.temp), t_str,
+            )
+        )
+            .fg(Color::White)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Isentropic Temp Ts:", units::get_temperature(result.outlet_s.t, app.units.temp), t_str,
+            )
+        )
+            .fg(Color::LightCyan)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Outlet Temp:", units::get_temperature(result.outlet.t, app.units.temp), t_str,
+            )
+        )
+            .fg(Color::LightCyan)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Isentropic Enthalpy Drop:", units::get_energy(result.inlet.h - result.outlet_s.h, app.units.energy, mm), energy_str,
+            )
+        )
+            .fg(Color::LightCyan)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Actual Enthalpy Drop:", units::get_energy(result.inlet.h - result.outlet.h, app.units.energy, mm), energy_str,
+            )
+        )
+            .fg(Color::LightCyan)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Recovered Power:", units::get_power(power, app.units.power), power_str,
+            )
+        )
+            .fg(Color::LightCyan)
+            .bg(Color::Black),
+    ];
+
+    if !app.expander_brake {
+        return items
+    }
+
+    if !(app.show_inlet_state && app.show_outlet_state) || calculations::pressure_ratio(app) <= 1.0 {
+        items.push(
+            ListItem::new("Brake compressor: set a compression inlet/outlet (I/O)")
+                .fg(Color::Red)
+                .bg(Color::Black)
+        );
+        return items
+    }
+
+    let head = calculations::isentropic_head(app);
+    let ts = calculations::isentropic_temp(app);
+    let hs = calculations::isentropic_enthalpy(app, ts);
+    let comp_eff = calculations::isentropic_eff(app, hs);
+    let comp_power = calculations::work(app);
+    let comp_mm = app.inlet_state.mm;
+    let balance = power - comp_power;
+    let balance_color = if balance < 0.0 { Color::Red } else { Color::LightCyan };
+
+    items.push(
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Shaft Speed:", app.input_speed, "RPM",
+            )
+        )
+            .fg(Color::LightYellow)
+            .bg(Color::Black)
+    );
+    items.push(
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Brake Comp Head:", units::get_energy(head, app.units.energy, comp_mm), energy_str,
+            )
+        )
+            .fg(Color::LightCyan)
+            .bg(Color::Black)
+    );
+    items.push(
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Brake Comp Efficiency:", comp_eff, "[]",
+            )
+        )
+            .fg(Color::LightCyan)
+            .bg(Color::Black)
+    );
+    items.push(
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Brake Comp Power:", units::get_power(comp_power, app.units.power), power_str,
+            )
+        )
+            .fg(Color::LightCyan)
+            .bg(Color::Black)
+    );
+    items.push(
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Shaft Power Balance:", units::get_power(balance, app.units.power), power_str,
+            )
+        )
+            .fg(balance_color)
+            .bg(Color::Black)
+    );
+    items
+}
@@ -0,0 +1,257 @@
+use std::f64::consts::PI;
+
+use ratatui::widgets::ListItem;
+use ratatui::
+    style::{
+        Color,
+        Stylize
+    };
+
+use crate::blowdown::{
+    self,
+    Sample,
+    WALL_CP,
+};
+use crate::eos::new_eos;
+use crate::flash;
+use crate::units::{
+    self,
+    PrintUnit,
+};
+use crate::App;
+
+// Fraction of the tank inventory added per time step
+const STEP_FRACTION: f64 = 0.01;
+const MAX_STEPS: usize = 5000;
+const MAX_TIME: f64 = 86400.0;
+const TABLE_ROWS: usize = 12;
+// SAE J2601 maximum gas temperature for hydrogen vehicle tanks
+const MAX_FILL_TEMP: f64 = 358.15;
+
+pub struct FillResult {
+    pub samples: Vec<Sample>,
+    pub target_reached: bool,
+    pub max_gas_temp: f64,
+    pub max_wall_temp: f64,
+}
+
+// Receiver as a real gas control volume filled from the current state: the
+// inflow carries the source enthalpy, and the U-V flash gives the tank state
+pub fn fill(app: &App) -> FillResult {
+    let (p_source, t_source) = (app.cur_state.p, app.cur_state.t);
+    let mut eos = new_eos(app.model, &app.gas_comp);
+    let source = eos.props_pt(p_source, t_source);
+    let mut state = eos.props_pt(app.fill_p0, app.fill_t0);
+    let volume_l = app.fill_volume * 1000.0;
+    let orifice_area = PI / 4.0 * app.fill_orifice_d * app.fill_orifice_d;
+    let area = blowdown::wall_area(app.fill_volume);
+    let heat_transfer = app.fill_wall_mass > 0.0;
+
+    let mut moles = state.d * volume_l;
+    // The time step is sized on the inventory, with a floor from the target
+    // inventory so an evacuated tank still advances
+    let min_moles = STEP_FRACTION * eos.props_pt(app.fill_target_p, app.fill_t0).d * volume_l;
+    let mut energy = moles * state.u;
+    let mut t_wall = app.fill_t0;
+    let mut time = 0.0;
+    let mut target_reached = false;
+    let mut max_gas_temp = state.t;
+    let mut max_wall_temp = t_wall;
+    let mut samples = vec![];
+
+    for _ in 0..MAX_STEPS {
+        let flow = if state.p >= p_source {
+            0.0
+        } else if app.fill_use_orifice {
+            app.fill_cd * orifice_area * blowdown::vent_mass_flux(&source, state.p)
+        } else {
+            app.fill_rate
+        };
+        samples.push(Sample {
+            time,
+            p: state.p,
+            t: state.t,
+            t_wall,
+            mass: moles * state.mm / 1000.0,
+            flow,
+        });
+        if state.p >= app.fill_target_p {
+            target_reached = true;
+            break;
+        }
+        if flow <= 0.0 || time >= MAX_TIME {
+            break;
+        }
+
+        // Molar fill rate in mol/s and wall heat input in W
+        let charge = flow / source.mm * 1000.0;
+        let dt = STEP_FRACTION * moles.max(min_moles) / charge;
+        let q = if heat_transfer { app.fill_h * area * (t_wall - state.t) } else { 0.0 };
+
+        moles += charge * dt;
+        energy += (q + charge * source.h) * dt;
+        if heat_transfer {
+            t_wall -= q * dt / (app.fill_wall_mass * WALL_CP);
+        }
+        time += dt;
+        state = flash::solve_du(eos.as_mut(), moles / volume_l, energy / moles, state.t);
+        max_gas_temp = max_gas_temp.max(state.t);
+        max_wall_temp = max_wall_temp.max(t_wall);
+    }
+
+    FillResult {
+        samples,
+        target_reached,
+        max_gas_temp,
+        max_wall_temp,
+    }
+}
+
+pub fn set_param(app: &mut App, val: f64) {
+    match app.input_param {
+        1 => app.fill_volume = blowdown::set_volume(app, val),
+        2 => app.fill_p0 = units::set_pressure(val, app.units.pressure),
+        3 => app.fill_t0 = units::set_temperature(val, app.units.temp),
+        4 => app.fill_target_p = units::set_pressure(val, app.units.pressure),
+        5 => app.fill_rate = units::set_flow(val, app.units.flow, &app.gas_comp, app.stp_60_F, app.model),
+        6 => app.fill_orifice_d = units::set_length(val, app.units.length),
+        7 => app.fill_cd = val,
+        8 => app.fill_wall_mass = if app.units.flow.is_imperial() { val / 2.20462 } else { val },
+        9 => app.fill_h = val,
+        _ => {}
+    }
+}
+
+pub fn run_filling(app: &App) -> Vec<ListItem<'static>> {
+    let p_str = app.units.pressure.print_unit();
+    let t_str = app.units.temp.print_unit();
+    let flow_str = app.units.flow.print_unit();
+    let (volume, volume_str) = blowdown::get_volume(app, app.fill_volume);
+    let (wall_mass, mass_str) = blowdown::get_mass(app, app.fill_wall_mass);
+    let mode_text = if app.fill_use_orifice { "Orifice" } else { "Fixed Rate" };
+
+    let inputs = [
+        ("1-Tank Volume:", volume, volume_str),
+        ("2-Initial Pressure:", units::get_pressure(app.fill_p0, app.units.pressure), p_str),
+        ("3-Initial Temp:", units::get_temperature(app.fill_t0, app.units.temp), t_str),
+        ("4-Target Pressure:", units::get_pressure(app.fill_target_p, app.units.pressure), p_str),
+        ("5-Fill Rate:", units::get_flow(app.fill_rate, app.units.flow, &app.gas_comp, app.stp_60_F, app.model), flow_str),
+        ("6-Orifice Diameter:", units::get_length(app.fill_orifice_d, app.units.length), app.units.length.print_unit()),
+        ("7-Discharge Coeff:", app.fill_cd, "[]"),
+        ("8-Wall Mass (0=adiab.):", wall_mass, mass_str),
+        ("9-Inside Film Coeff:", app.fill_h, "W/(m^2-K)"),
+    ];
+    let mut items: Vec<ListItem<'static>> = inputs
+        .iter()
+        .map(|(label, val, unit)| {
+            ListItem::new(
+                format!("{:<24} {:.4} {}",
+                    label, val, unit,
+                )
+            )
+                .fg(Color::LightYellow)
+                .bg(Color::Black)
+        })
+        .collect();
+    items.push(
+        ListItem::new(
+            format!("{:<24} {}",
+                "R-Fill Mode:", mode_text,
+            )
+        )
+            .fg(Color::LightYellow)
+            .bg(Color::Black)
+    );
+
+    let flow_set = if app.fill_use_orifice { app.fill_orifice_d > 0.0 } else { app.fill_rate > 0.0 };
+    if app.fill_volume <= 0.0 || !flow_set {
+        items.push(
+            ListItem::new("Enter the tank volume (1) and a fill rate (5) or orifice (6)")
+                .fg(Color::Red)
+                .bg(Color::Black)
+        );
+        return items
+    }
+
+    if app.fill_p0 < 0.0 || app.fill_target_p <= app.fill_p0 {
+        items.push(
+            ListItem::new("The target pressure (4) must exceed the initial pressure (2), which cannot be negative")
+                .fg(Color::Red)
+                .bg(Color::Black)
+        );
+        return items
+    }
+
+    let result = fill(app);
+    let first = &result.samples[0];
+    let last = &result.samples[result.samples.len() - 1];
+    let (delivered, delivered_str) = blowdown::get_mass(app, last.mass - first.mass);
+    let status_text = if result.target_reached { "Target pressure reached" } else { "Stopped at source pressure" };
+    let temp_color = if result.max_gas_temp > MAX_FILL_TEMP { Color::Red } else { Color::LightCyan };
+
+    items.push(
+        ListItem::new(
+            format!("{:<24} {}",
+                "Fill Status:", status_text,
+            )
+        )
+            .fg(if result.target_reached { Color::LightCyan } else { Color::Red })
+            .bg(Color::Black)
+    );
+    let rows = [
+        ("Fill Time:", last.time, "s", Color::LightCyan),
+        ("Mass Delivered:", delivered, delivered_str, Color::LightCyan),
+        ("Final Pressure:", units::get_pressure(last.p, app.units.pressure), p_str, Color::LightCyan),
+        ("Final Gas Temp:", units::get_temperature(last.t, app.units.temp), t_str, Color::LightCyan),
+        ("Max Gas Temp:", units::get_temperature(result.max_gas_temp, app.units.temp), t_str, temp_color),
+        ("Max Wall Temp:", units::get_temperature(result.max_wall_temp, app.units.temp), t_str, Color::LightCyan),
+    ];
+    for (label, val, unit, color) in rows {
+        items.push(
+            ListItem::new(
+                format!("{:<24} {:.4} {}",
+                    label, val, unit,
+                )
+            )
+                .fg(color)
+                .bg(Color::Black)
+        );
+    }
+
+    items.push(
+        ListItem::new(
+            format!("{:<10} {:<14} {:<12} {:<12} {:<14} Flow ({})",
+                "Time (s)",
+                format!("Press ({})", p_str),
+                format!("Gas ({})", t_str),
+                format!("Wall ({})", t_str),
+                format!("Mass ({})", delivered_str),
+                flow_str,
+            )
+        )
+            .fg(Color::White)
+            .bg(Color::Black)
+    );
+    let mut next_time = 0.0;
+    for (i, sample) in result.samples.iter().enumerate() {
+        if sample.time < next_time && i + 1 != result.samples.len() {
+            continue;
+        }
+        next_time = sample.time + last.time / TABLE_ROWS as f64;
+        items.push(
+            ListItem::new(
+                format!("{:<10.1} {:<14.4} {:<12.4} {:<12.4} {:<14.4} {:.4}",
+                    sample.time,
+                    units::get_pressure(sample.p, app.units.pressure),
+                    units::get_temperature(sample.t, app.units.temp),
+                    units::get_temperature(sample.t_wall, app.units.temp),
+                    blowdown::get_mass(app, sample.mass).0,
+                    units::get_flow(sample.flow, app.units.flow, &app.gas_comp, app.stp_60_F, app.model),
+                )
+            )
+                .fg(Color::LightCyan)
+                .bg(Color::Black)
+        );
+    }
+    items
+}
@@ -0,0 +1,110 @@
+use aga8::composition::Composition;
+
+use crate::eos::{
+    new_eos,
+    Eos,
+    Model,
+};
+pub use crate::eos::GasProps;
+
+const MAX_ITER: usize = 50;
+const MIN_TEMP: f64 = 10.0;
+
+// Properties at pressure (kPa) and temperature (K)
+pub fn props_pt(gas_comp: &Composition, model: Model, p: f64, t: f64) -> GasProps {
+    new_eos(model, gas_comp).props_pt(p, t)
+}
+
+// Temperature at pressure (kPa) and enthalpy (J/mol)
+pub fn props_ph(gas_comp: &Composition, model: Model, p: f64, h: f64, t_guess: f64) -> GasProps {
+    solve_ph(new_eos(model, gas_comp).as_mut(), p, h, t_guess)
+}
+
+// Temperature at pressure (kPa) and entropy (J/(mol-K))
+pub fn props_ps(gas_comp: &Composition, model: Model, p: f64, s: f64, t_guess: f64) -> GasProps {
+    solve_ps(new_eos(model, gas_comp).as_mut(), p, s, t_guess)
+}
+
+// Newton iteration on cp for the temperature giving enthalpy h (J/mol) at
+// pressure p (kPa), reusing the caller's equation of state
+pub fn solve_ph(eos: &mut dyn Eos, p: f64, h: f64, t_guess: f64) -> GasProps {
+    let mut t = t_guess.max(MIN_TEMP);
+    let mut props = eos.props_pt(p, t);
+    for _ in 0..MAX_ITER {
+        let dt = (h - props.h) / props.cp;
+        t = (t + dt).max(MIN_TEMP);
+        props = eos.props_pt(p, t);
+        if dt.abs() < 1.0e-6 {
+            break;
+        }
+    }
+    props
+}
+
+// Newton iteration on cp/T for the temperature giving entropy s (J/(mol-K))
+// at pressure p (kPa)
+pub fn solve_ps(eos: &mut dyn Eos, p: f64, s: f64, t_guess: f64) -> GasProps {
+    let mut t = t_guess.max(MIN_TEMP);
+    let mut props = eos.props_pt(p, t);
+    for _ in 0..MAX_ITER {
+        let dt = (s - props.s) * t / props.cp;
+        t = (t + dt).max(MIN_TEMP);
+        props = eos.props_pt(p, t);
+        if dt.abs() < 1.0e-6 {
+            break;
+        }
+    }
+    props
+}
+
+// Newton iteration on cv for the temperature giving internal energy u (J/mol)
+// at molar density d (mol/l)
+pub fn solve_du(eos: &mut dyn Eos, d: f64, u: f64, t_guess: f64) -> GasProps {
+    let mut t = t_guess.max(MIN_TEMP);
+    let mut props = eos.props_td(t, d);
+    for _ in 0..MAX_ITER {
+        let dt = (u - props.u) / props.cv;
+        t = (t + dt).max(MIN_TEMP);
+        props = eos.props_td(t, d);
+        if dt.abs() < 1.0e-6 {
+            break;
+        }
+    }
+    props
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eos::tests::DEMO_COMP;
+
+    // GERG-2008 reference program state at 400 K and 50 MPa, recovered from
+    // each pair of its published properties
+    const P: f64 = 50000.0;
+    const T: f64 = 400.0;
+    const D: f64 = 12.798286260821;
+    const U: f64 = -2746.492901213;
+    const H: f64 = 1160.280160511;
+    const S: f64 = -38.575903924091;
+
+    #[test]
+    fn pressure_enthalpy() {
+        let props = props_ph(&DEMO_COMP, Model::Gerg2008, P, H, 300.0);
+        assert!((props.t - T).abs() < 1.0e-6, "T {}", props.t);
+        assert!((props.d - D).abs() < 1.0e-6, "D {}", props.d);
+    }
+
+    #[test]
+    fn pressure_entropy() {
+        let props = props_ps(&DEMO_COMP, Model::Gerg2008, P, S, 300.0);
+        assert!((props.t - T).abs() < 1.0e-6, "T {}", props.t);
+        assert!((props.h - H).abs() < 1.0e-3, "H {}", props.h);
+    }
+
+    #[test]
+    fn density_energy() {
+        let props = solve_du(new_eos(Model::Gerg2008, &DEMO_COMP).as_mut(), D, U, 300.0);
+        assert!((props.t - T).abs() < 1.0e-6, "T {}", props.t);
+        assert!((props.p - P).abs() < 1.0e-3, "P {}", props.p);
+    }
+}
@@ -0,0 +1,241 @@
+use aga8::composition::Composition;
+use ratatui::widgets::ListItem;
+use ratatui::
+    style::{
+        Color,
+        Stylize
+    };
+
+use crate::eos::{
+    new_eos,
+    Eos,
+    Model,
+    R,
+};
+use crate::flash::GasProps;
+use crate::gas::{
+    components,
+    mix_composition,
+    pure_component,
+};
+use crate::units::{
+    self,
+    PrintUnit,
+};
+use crate::App;
+
+// Relative mole number step for the composition derivatives, and the
+// fraction below which the step stops shrinking with the mole fraction. The
+// smallest step stays well above the 1e-7 mole fraction change the AGA8 and
+// GERG-2008 models need before they update their mixing parameters.
+const STEP: f64 = 1.0e-4;
+const MIN_STEP_FRACTION: f64 = 1.0e-2;
+
+// 10-point Gauss-Legendre nodes and weights on [-1, 1]
+const GAUSS: [(f64, f64); 10] = [
+    (-0.9739065285171717, 0.0666713443086881),
+    (-0.8650633666889845, 0.1494513491505806),
+    (-0.6794095682990244, 0.219086362515982),
+    (-0.4333953941292472, 0.2692667193099963),
+    (-0.1488743389816312, 0.2955242247147529),
+    (0.1488743389816312, 0.2955242247147529),
+    (0.4333953941292472, 0.2692667193099963),
+    (0.6794095682990244, 0.219086362515982),
+    (0.8650633666889845, 0.1494513491505806),
+    (0.9739065285171717, 0.0666713443086881),
+];
+
+pub struct Fugacity {
+    pub name: &'static str,
+    pub x: f64,
+    pub phi: f64,
+    // Fugacity in kPa
+    pub fugacity: f64,
+    // Chemical potential in J/mol of the component, on the EOS datum
+    pub mu: f64,
+    // Component molar mass in g/mol
+    pub mm: f64,
+}
+
+// Reduced residual Helmholtz energy a_r/RT at t and molar density d from
+// the integral of (Z - 1)/d over density, so it works with any model
+fn residual_helmholtz(eos: &mut dyn Eos, t: f64, d: f64) -> f64 {
+    GAUSS
+        .iter()
+        .map(|(node, weight)| {
+            let rho = 0.5 * d * (1.0 + node);
+            weight * (eos.props_td(t, rho).z - 1.0) / rho
+        })
+        .sum::<f64>()
+        * 0.5 * d
+}
+
+// Natural log of the fugacity coefficient of component index at the state,
+// from the mole number derivative of n a_r/RT at constant T and V. The eos
+// composition is changed and restored to gas_comp.
+pub fn ln_phi(eos: &mut dyn Eos, gas_comp: &Composition, state: &GasProps, index: usize) -> f64 {
+    let pure = pure_component(index);
+    let x = components(gas_comp)[index].1;
+    // Relative step, kept above round-off for trace components
+    let step = STEP * x.max(MIN_STEP_FRACTION);
+    let mut total = |dn: f64| {
+        eos.set_composition(&mix_composition(&[(gas_comp, 1.0), (&pure, dn)]));
+        (1.0 + dn) * residual_helmholtz(eos, state.t, state.d * (1.0 + dn))
+    };
+    // Forward difference when a backward step would leave a negative amount
+    let derivative = if step < x {
+        (total(step) - total(-step)) / (2.0 * step)
+    } else {
+        (total(step) - total(0.0)) / step
+    };
+    eos.set_composition(gas_comp);
+    derivative - state.z.ln()
+}
+
+// Fugacity table for the non-zero components of the state
+pub fn fugacities(gas_comp: &Composition, model: Model, state: &GasProps) -> Vec<Fugacity> {
+    let mut eos = new_eos(model, gas_comp);
+    let mut ideal_gas = new_eos(Model::IdealGas, gas_comp);
+    components(gas_comp)
+        .iter()
+        .enumerate()
+        .filter(|(_, (_, x))| *x > 0.0)
+        .map(|(index, (name, x))| {
+            let phi = ln_phi(eos.as_mut(), gas_comp, state, index).exp();
+            // Pure ideal gas at the same T and P
+            ideal_gas.set_composition(&pure_component(index));
+            let ideal = ideal_gas.props_pt(state.p, state.t);
+            Fugacity {
+                name,
+                x: *x,
+                phi,
+                fugacity: x * phi * state.p,
+                mu: ideal.g + R * state.t * (x * phi).ln(),
+                mm: ideal.mm,
+            }
+        })
+        .collect()
+}
+
+pub fn run_fugacity(app: &mut App) -> Vec<ListItem<'static>> {
+    let p_str = app.units.pressure.print_unit();
+    let t_str = app.units.temp.print_unit();
+    let state = app.cur_state;
+    let mut items = vec![
+        ListItem::new(
+            format!("Current state at {:.4} {}, {:.4} {} ({})",
+                units::get_pressure(state.p, app.units.pressure), p_str,
+                units::get_temperature(state.t, app.units.temp), t_str,
+                app.model.name(),
+            )
+        )
+            .fg(Color::White)
+            .bg(Color::Blue),
+
+        ListItem::new(
+            format!("{:<18} {:>10} {:>12} {:>14} {:>16}",
+                "Component", "x", "Phi", format!("f ({})", p_str),
+                format!("Mu ({})", app.units.energy.print_unit()),
+            )
+        )
+            .fg(Color::White)
+            .bg(Color::Black),
+    ];
+
+    let body: Vec<ListItem<'static>> = fugacities(&app.gas_comp, app.model, &state)
+        .iter()
+        .map(|row| {
+            ListItem::new(
+                format!("{:<18} {:>10.6} {:>12.6} {:>14.4} {:>16.4}",
+                    row.name,
+                    row.x,
+                    row.phi,
+                    units::get_pressure(row.fugacity, app.units.pressure),
+                    units::get_energy(row.mu, app.units.energy, row.mm),
+                )
+            )
+                .fg(Color::LightCyan)
+                .bg(Color::Black)
+        })
+        .collect();
+
+    app.fugacity_scroll = app.fugacity_scroll.min(body.len().saturating_sub(1));
+    items.extend(body.into_iter().skip(app.fugacity_scroll));
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::CRITICAL_DATA;
+
+    // Methane, nitrogen, carbon dioxide, ethane and propane
+    fn natural_gas() -> Composition {
+        mix_composition(&[
+            (&pure_component(0), 0.85),
+            (&pure_component(1), 0.03),
+            (&pure_component(2), 0.03),
+            (&pure_component(3), 0.06),
+            (&pure_component(4), 0.03),
+        ])
+    }
+
+    // Closed form for a pure fluid (Peng and Robinson, 1976):
+    // ln phi = Z - 1 - ln(Z - B) - A / (2 sqrt2 B) ln((Z + 2.414 B) / (Z - 0.414 B))
+    #[test]
+    fn peng_robinson_pure_closed_form() {
+        let methane = pure_component(0);
+        let (p, t) = (5000.0, 300.0);
+        let state = new_eos(Model::PengRobinson, &methane).props_pt(p, t);
+        let (tc, pc, omega) = CRITICAL_DATA[0];
+        let m = 0.37464 + 1.54226 * omega - 0.26992 * omega * omega;
+        let alpha = (1.0 + m * (1.0 - (t / tc).sqrt())).powi(2);
+        let a = 0.45724 * R * R * tc * tc / pc * alpha * p / (R * t).powi(2);
+        let b = 0.07780 * R * tc / pc * p / (R * t);
+        let sqrt2 = 2.0_f64.sqrt();
+        let z = state.z;
+        let expected = z - 1.0 - (z - b).ln()
+            - a / (2.0 * sqrt2 * b) * ((z + (1.0 + sqrt2) * b) / (z + (1.0 - sqrt2) * b)).ln();
+        let fugacity = fugacities(&methane, Model::PengRobinson, &state);
+        assert!((fugacity[0].phi.ln() - expected).abs() < 1.0e-6, "{} {}", fugacity[0].phi.ln(), expected);
+    }
+
+    // Mole fraction sum of ln phi is the residual Gibbs energy over RT, and
+    // the chemical potentials sum to the molar Gibbs energy
+    #[test]
+    fn mixture_sums() {
+        let gas_comp = natural_gas();
+        for model in [Model::Detail, Model::Gerg2008, Model::PengRobinson] {
+            let state = new_eos(model, &gas_comp).props_pt(10000.0, 300.0);
+            let ideal = new_eos(Model::IdealGas, &gas_comp).props_pt(10000.0, 300.0);
+            let table = fugacities(&gas_comp, model, &state);
+            let sum_ln_phi: f64 = table.iter().map(|f| f.x * f.phi.ln()).sum();
+            let sum_mu: f64 = table.iter().map(|f| f.x * f.mu).sum();
+            assert!((sum_ln_phi - (state.g - ideal.g) / (R * 300.0)).abs() < 1.0e-4, "{}", model.name());
+            assert!((sum_mu - state.g).abs() < 0.2, "{}", model.name());
+        }
+    }
+
+    // A trace component approaches its infinite dilution value smoothly
+    #[test]
+    fn trace_component() {
+        let helium = pure_component(19);
+        let phi = |amount: f64| {
+            let comp = mix_composition(&[(&pure_component(0), 1.0), (&helium, amount)]);
+            let state = new_eos(Model::Gerg2008, &comp).props_pt(10000.0, 300.0);
+            let table = fugacities(&comp, Model::Gerg2008, &state);
+            table.iter().find(|f| f.x < 0.5).map(|f| f.phi).unwrap()
+        };
+        let (trace, dilute) = (phi(1.0e-7), phi(1.0e-5));
+        assert!((trace / dilute - 1.0).abs() < 1.0e-4, "{} {}", trace, dilute);
+    }
+
+    #[test]
+    fn ideal_gas_is_unity() {
+        let gas_comp = natural_gas();
+        let state = new_eos(Model::IdealGas, &gas_comp).props_pt(10000.0, 300.0);
+        for fugacity in fugacities(&gas_comp, Model::IdealGas, &state) {
+            assert!((fugacity.phi - 1.0).abs() < 1.0e-9, "{} {}", fugacity.name, fugacity.phi);
+        }
+    }
+}
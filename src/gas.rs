@@ -58,11 +58,113 @@ pub fn get_gas_comp(gas_comp: Gas) -> Composition{
 }
 
 pub fn set_gas(app: &mut App, composition: Composition) {
-    app.aga8_cur_state.set_composition(&composition);
-    app.gerg_cur_state.set_composition(&composition);
-    app.aga8_inlet_state.set_composition(&composition);
-    app.gerg_inlet_state.set_composition(&composition);
-    app.aga8_outlet_state.set_composition(&composition);
-    app.gerg_outlet_state.set_composition(&composition);
+    app.gas_comp = composition;
     recalculate(app);
+}
+
+// Ideal gross heating values at 15 C (kJ/mol) from ISO 6976, in the order
+// of components()
+const GROSS_HEATING_VALUES: [f64; 21] = [
+    891.51, 0.0, 0.0, 1562.14, 2221.10, 2870.58, 2879.76, 3531.68, 3538.60, 4198.24,
+    4857.18, 5516.01, 6175.82, 6834.93, 286.15, 0.0, 282.91, 44.224, 562.01, 0.0, 0.0,
+];
+
+// Ideal gross heating value of the mixture (kJ/mol)
+pub fn gross_heating_value(comp: &Composition) -> f64 {
+    components(comp)
+        .iter()
+        .zip(GROSS_HEATING_VALUES.iter())
+        .map(|((_, x), hv)| x * hv)
+        .sum()
+}
+
+pub fn components(comp: &Composition) -> [(&'static str, f64); 21] {
+    [
+        ("Methane", comp.methane),
+        ("Nitrogen", comp.nitrogen),
+        ("Carbon Dioxide", comp.carbon_dioxide),
+        ("Ethane", comp.ethane),
+        ("Propane", comp.propane),
+        ("Isobutane", comp.isobutane),
+        ("n-Butane", comp.n_butane),
+        ("Isopentane", comp.isopentane),
+        ("n-Pentane", comp.n_pentane),
+        ("Hexane", comp.hexane),
+        ("Heptane", comp.heptane),
+        ("Octane", comp.octane),
+        ("Nonane", comp.nonane),
+        ("Decane", comp.decane),
+        ("Hydrogen", comp.hydrogen),
+        ("Oxygen", comp.oxygen),
+        ("Carbon Monoxide", comp.carbon_monoxide),
+        ("Water", comp.water),
+        ("Hydrogen Sulfide", comp.hydrogen_sulfide),
+        ("Helium", comp.helium),
+        ("Argon", comp.argon),
+    ]
+}
+
+// Mutable mole fraction of a component, by its index in components()
+pub fn component_mut(comp: &mut Composition, index: usize) -> &mut f64 {
+    match index {
+        0 => &mut comp.methane,
+        1 => &mut comp.nitrogen,
+        2 => &mut comp.carbon_dioxide,
+        3 => &mut comp.ethane,
+        4 => &mut comp.propane,
+        5 => &mut comp.isobutane,
+        6 => &mut comp.n_butane,
+        7 => &mut comp.isopentane,
+        8 => &mut comp.n_pentane,
+        9 => &mut comp.hexane,
+        10 => &mut comp.heptane,
+        11 => &mut comp.octane,
+        12 => &mut comp.nonane,
+        13 => &mut comp.decane,
+        14 => &mut comp.hydrogen,
+        15 => &mut comp.oxygen,
+        16 => &mut comp.carbon_monoxide,
+        17 => &mut comp.water,
+        18 => &mut comp.hydrogen_sulfide,
+        19 => &mut comp.helium,
+        _ => &mut comp.argon,
+    }
+}
+
+// Composition of a single component, by its index in components()
+pub fn pure_component(index: usize) -> Composition {
+    let mut comp = Composition::default();
+    *component_mut(&mut comp, index) = 1.0;
+    comp
+}
+
+// Mole-weighted blend of compositions, each paired with its molar amount
+pub fn mix_composition(comps: &[(&Composition, f64)]) -> Composition {
+    let total: f64 = comps.iter().map(|(_, n)| n).sum();
+    let mix = |f: fn(&Composition) -> f64| {
+        comps.iter().map(|(c, n)| f(c) * n).sum::<f64>() / total
+    };
+    Composition {
+        methane: mix(|c| c.methane),
+        nitrogen: mix(|c| c.nitrogen),
+        carbon_dioxide: mix(|c| c.carbon_dioxide),
+        ethane: mix(|c| c.ethane),
+        propane: mix(|c| c.propane),
+        isobutane: mix(|c| c.isobutane),
+        n_butane: mix(|c| c.n_butane),
+        isopentane: mix(|c| c.isopentane),
+        n_pentane: mix(|c| c.n_pentane),
+        hexane: mix(|c| c.hexane),
+        heptane: mix(|c| c.heptane),
+        octane: mix(|c| c.octane),
+        nonane: mix(|c| c.nonane),
+        decane: mix(|c| c.decane),
+        hydrogen: mix(|c| c.hydrogen),
+        oxygen: mix(|c| c.oxygen),
+        carbon_monoxide: mix(|c| c.carbon_monoxide),
+        water: mix(|c| c.water),
+        hydrogen_sulfide: mix(|c| c.hydrogen_sulfide),
+        helium: mix(|c| c.helium),
+        argon: mix(|c| c.argon),
+    }
 }
\ No newline at end of file
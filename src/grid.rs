@@ -0,0 +1,475 @@
+use ratatui::widgets::ListItem;
+use ratatui::
+    style::{
+        Color,
+        Stylize
+    };
+
+use crate::eos::new_eos;
+use crate::sweep::{
+    csv_field,
+    range,
+    write_export,
+};
+use crate::units::{
+    self,
+    PrintUnit,
+};
+use crate::App;
+
+// Upper limits on the points per axis and on the grid size
+const MAX_AXIS_POINTS: usize = 200;
+const MAX_NODES: usize = 2500;
+// Stem of the file the grid is exported to, in the working directory
+const FILE_NAME: &str = "gas_calc_grid";
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Format {
+    Csv,
+    Json,
+    CHeader,
+    StructuredText,
+}
+impl Format {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Format::Csv => "CSV",
+            Format::Json => "JSON Array",
+            Format::CHeader => "C Header",
+            Format::StructuredText => "Structured Text",
+        }
+    }
+
+    pub fn next(&self) -> Format {
+        match self {
+            Format::Csv => Format::Json,
+            Format::Json => Format::CHeader,
+            Format::CHeader => Format::StructuredText,
+            Format::StructuredText => Format::Csv,
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            Format::Csv => "csv",
+            Format::Json => "json",
+            Format::CHeader => "h",
+            Format::StructuredText => "st",
+        }
+    }
+}
+
+// Tabulated property: display name, identifier for the code formats, unit
+// and values indexed [pressure][temperature]
+pub struct PropertyTable {
+    pub name: &'static str,
+    pub ident: &'static str,
+    pub unit: &'static str,
+    pub values: Vec<Vec<f64>>,
+}
+
+// Largest bilinear lookup error of a table at the cell midpoints, with the
+// midpoint pressure and temperature in display units
+pub struct InterpError {
+    pub abs: f64,
+    pub percent: f64,
+    pub p: f64,
+    pub t: f64,
+}
+
+// Grid axes in display units and the property tables
+pub struct Grid {
+    pub pressure: Vec<f64>,
+    pub temperature: Vec<f64>,
+    pub tables: Vec<PropertyTable>,
+}
+
+// Density, Z and speed of sound in display units at each (p, t); p and t in
+// kPa and K
+fn properties(app: &App, p: &[f64], t: &[f64]) -> Result<Vec<Vec<[f64; 3]>>, &'static str> {
+    let mut eos = new_eos(app.model, &app.gas_comp);
+    let mut values = vec![];
+    for p in p {
+        let mut row = vec![];
+        for t in t {
+            let props = eos.props_pt(*p, *t);
+            let node = [
+                units::get_density(props.d, app.units.density, props.mm),
+                props.z,
+                units::get_speed(props.w, app.units.speed),
+            ];
+            if node.iter().any(|val| !val.is_finite()) {
+                return Err("Equation of state failed inside the grid")
+            }
+            row.push(node);
+        }
+        values.push(row);
+    }
+    Ok(values)
+}
+
+// Pressure and temperature axes in kPa and K
+fn axes(app: &App) -> Result<(Vec<f64>, Vec<f64>), &'static str> {
+    if app.grid_p_start <= 0.0 || app.grid_p_stop <= 0.0 || app.grid_t_start <= 0.0 || app.grid_t_stop <= 0.0 {
+        return Err("Pressure and temperature must be greater than zero")
+    }
+    let p = range(app.grid_p_start, app.grid_p_stop, app.grid_p_step, MAX_AXIS_POINTS)?;
+    let t = range(app.grid_t_start, app.grid_t_stop, app.grid_t_step, MAX_AXIS_POINTS)?;
+    if p.len() < 2 || t.len() < 2 {
+        return Err("The grid needs at least two points on each axis")
+    }
+    if p.len() * t.len() > MAX_NODES {
+        return Err("Too many grid points, increase the steps")
+    }
+    Ok((p, t))
+}
+
+pub fn grid(app: &App) -> Result<Grid, &'static str> {
+    let (p, t) = axes(app)?;
+    let values = properties(app, &p, &t)?;
+    let table = |index: usize| -> Vec<Vec<f64>> {
+        values
+            .iter()
+            .map(|row| row.iter().map(|node| node[index]).collect())
+            .collect()
+    };
+    Ok(Grid {
+        pressure: p.iter().map(|p| units::get_pressure(*p, app.units.pressure)).collect(),
+        temperature: t.iter().map(|t| units::get_temperature(*t, app.units.temp)).collect(),
+        tables: vec![
+            PropertyTable {
+                name: "Density",
+                ident: "density",
+                unit: app.units.density.print_unit(),
+                values: table(0),
+            },
+            PropertyTable {
+                name: "Z",
+                ident: "z",
+                unit: "[]",
+                values: table(1),
+            },
+            PropertyTable {
+                name: "Speed of Sound",
+                ident: "speed_of_sound",
+                unit: app.units.speed.print_unit(),
+                values: table(2),
+            },
+        ],
+    })
+}
+
+// Maximum error of bilinear interpolation against the equation of state at
+// the cell midpoints, where the lookup is furthest from the nodes
+pub fn interpolation_errors(app: &App, grid: &Grid) -> Result<Vec<InterpError>, &'static str> {
+    let (p, t) = axes(app)?;
+    let p_mid: Vec<f64> = p.windows(2).map(|w| 0.5 * (w[0] + w[1])).collect();
+    let t_mid: Vec<f64> = t.windows(2).map(|w| 0.5 * (w[0] + w[1])).collect();
+    let exact = properties(app, &p_mid, &t_mid)?;
+    let mut errors: Vec<InterpError> = grid.tables
+        .iter()
+        .map(|_| InterpError {
+            abs: 0.0,
+            percent: 0.0,
+            p: 0.0,
+            t: 0.0,
+        })
+        .collect();
+    for (i, row) in exact.iter().enumerate() {
+        for (j, node) in row.iter().enumerate() {
+            for (k, table) in grid.tables.iter().enumerate() {
+                let v = &table.values;
+                // Bilinear interpolation at a cell centre is the corner mean
+                let lookup = 0.25 * (v[i][j] + v[i + 1][j] + v[i][j + 1] + v[i + 1][j + 1]);
+                let percent = (lookup - node[k]) / node[k].abs() * 100.0;
+                if percent.abs() > errors[k].percent.abs() {
+                    errors[k] = InterpError {
+                        abs: lookup - node[k],
+                        percent,
+                        p: units::get_pressure(p_mid[i], app.units.pressure),
+                        t: units::get_temperature(t_mid[j], app.units.temp),
+                    };
+                }
+            }
+        }
+    }
+    Ok(errors)
+}
+
+fn write_csv(app: &App, grid: &Grid) -> String {
+    let mut headers = vec![
+        format!("Pressure ({})", app.units.pressure.print_unit()),
+        format!("Temperature ({})", app.units.temp.print_unit()),
+    ];
+    headers.extend(grid.tables.iter().map(|table| format!("{} ({})", table.name, table.unit)));
+    let mut text = headers
+        .iter()
+        .map(|header| csv_field(header))
+        .collect::<Vec<String>>()
+        .join(",");
+    text.push('\n');
+    for (i, p) in grid.pressure.iter().enumerate() {
+        for (j, t) in grid.temperature.iter().enumerate() {
+            let mut line = format!("{},{}", p, t);
+            for table in &grid.tables {
+                line.push_str(&format!(",{}", table.values[i][j]));
+            }
+            text.push_str(&line);
+            text.push('\n');
+        }
+    }
+    text
+}
+
+fn join(values: &[f64], format: fn(&f64) -> String) -> String {
+    values.iter().map(format).collect::<Vec<String>>().join(", ")
+}
+
+// Array of axis and table objects; table values are nested [pressure][temperature]
+fn write_json(app: &App, grid: &Grid) -> String {
+    let json = |val: &f64| format!("{}", val);
+    let mut entries = vec![
+        format!("  {{\"name\": \"Pressure\", \"unit\": \"{}\", \"values\": [{}]}}",
+            app.units.pressure.print_unit(), join(&grid.pressure, json)),
+        format!("  {{\"name\": \"Temperature\", \"unit\": \"{}\", \"values\": [{}]}}",
+            app.units.temp.print_unit(), join(&grid.temperature, json)),
+    ];
+    for table in &grid.tables {
+        let rows = table.values
+            .iter()
+            .map(|row| format!("    [{}]", join(row, json)))
+            .collect::<Vec<String>>()
+            .join(",\n");
+        entries.push(
+            format!("  {{\"name\": \"{}\", \"unit\": \"{}\", \"values\": [\n{}\n  ]}}",
+                table.name, table.unit, rows)
+        );
+    }
+    format!("[\n{}\n]\n", entries.join(",\n"))
+}
+
+fn write_c_header(app: &App, grid: &Grid) -> String {
+    let c = |val: &f64| format!("{:e}", val);
+    let (np, nt) = (grid.pressure.len(), grid.temperature.len());
+    let mut text = format!(
+        "/* Gas property lookup tables ({}), rows are pressure and columns temperature */\n\
+        #ifndef GAS_CALC_GRID_H\n\
+        #define GAS_CALC_GRID_H\n\n\
+        #define GRID_P_COUNT {}\n\
+        #define GRID_T_COUNT {}\n\n\
+        /* Pressure ({}) */\n\
+        static const double grid_pressure[GRID_P_COUNT] = {{{}}};\n\n\
+        /* Temperature ({}) */\n\
+        static const double grid_temperature[GRID_T_COUNT] = {{{}}};\n",
+        app.model.name(),
+        np,
+        nt,
+        app.units.pressure.print_unit(), join(&grid.pressure, c),
+        app.units.temp.print_unit(), join(&grid.temperature, c),
+    );
+    for table in &grid.tables {
+        let rows = table.values
+            .iter()
+            .map(|row| format!("    {{{}}}", join(row, c)))
+            .collect::<Vec<String>>()
+            .join(",\n");
+        text.push_str(
+            &format!("\n/* {} ({}) */\nstatic const double grid_{}[GRID_P_COUNT][GRID_T_COUNT] = {{\n{}\n}};\n",
+                table.name, table.unit, table.ident, rows)
+        );
+    }
+    text.push_str("\n#endif\n");
+    text
+}
+
+// IEC 61131-3 global constants with 1-based bounds
+fn write_structured_text(app: &App, grid: &Grid) -> String {
+    let st = |val: &f64| format!("{:e}", val);
+    let (np, nt) = (grid.pressure.len(), grid.temperature.len());
+    let mut text = [
+        format!("(* Gas property lookup tables ({}), first index is pressure and second temperature *)", app.model.name()),
+        "VAR_GLOBAL CONSTANT".to_string(),
+        format!("    GRID_P_COUNT : INT := {};", np),
+        format!("    GRID_T_COUNT : INT := {};", nt),
+        format!("    (* Pressure ({}) *)", app.units.pressure.print_unit()),
+        format!("    GRID_PRESSURE : ARRAY[1..{}] OF LREAL := [{}];", np, join(&grid.pressure, st)),
+        format!("    (* Temperature ({}) *)", app.units.temp.print_unit()),
+        format!("    GRID_TEMPERATURE : ARRAY[1..{}] OF LREAL := [{}];", nt, join(&grid.temperature, st)),
+    ]
+        .join("\n");
+    text.push('\n');
+    for table in &grid.tables {
+        let rows = table.values
+            .iter()
+            .map(|row| format!("        {}", join(row, st)))
+            .collect::<Vec<String>>()
+            .join(",\n");
+        text.push_str(
+            &format!("    (* {} ({}) *)\n    GRID_{} : ARRAY[1..{}, 1..{}] OF LREAL := [\n{}\n    ];\n",
+                table.name, table.unit, table.ident.to_uppercase(), np, nt, rows)
+        );
+    }
+    text.push_str("END_VAR\n");
+    text
+}
+
+pub fn export(app: &mut App) {
+    let result = grid(app).map_err(|msg| msg.to_string()).and_then(|grid| {
+        let text = match app.grid_format {
+            Format::Csv => write_csv(app, &grid),
+            Format::Json => write_json(app, &grid),
+            Format::CHeader => write_c_header(app, &grid),
+            Format::StructuredText => write_structured_text(app, &grid),
+        };
+        write_export(FILE_NAME, app.grid_format.extension(), &text)
+    });
+    app.grid_message = match result {
+        Ok(path) => format!("Exported grid to {}", path.display()),
+        Err(msg) => format!("Export failed: {}", msg),
+    };
+}
+
+pub fn set_param(app: &mut App, val: f64) {
+    let t_step = |val: f64| val.abs() / units::get_temperature_difference(1.0, app.units.temp);
+    match app.input_param {
+        1 => app.grid_p_start = units::set_pressure(val, app.units.pressure),
+        2 => app.grid_p_stop = units::set_pressure(val, app.units.pressure),
+        3 => app.grid_p_step = units::set_pressure(val.abs(), app.units.pressure),
+        4 => app.grid_t_start = units::set_temperature(val, app.units.temp),
+        5 => app.grid_t_stop = units::set_temperature(val, app.units.temp),
+        6 => app.grid_t_step = t_step(val),
+        _ => {}
+    }
+    app.grid_message.clear();
+}
+
+pub fn run_grid(app: &App) -> Vec<ListItem<'static>> {
+    let p_str = app.units.pressure.print_unit();
+    let t_str = app.units.temp.print_unit();
+    let inputs = [
+        ("1-Pressure Start:", units::get_pressure(app.grid_p_start, app.units.pressure), p_str),
+        ("2-Pressure Stop:", units::get_pressure(app.grid_p_stop, app.units.pressure), p_str),
+        ("3-Pressure Step:", units::get_pressure(app.grid_p_step, app.units.pressure), p_str),
+        ("4-Temperature Start:", units::get_temperature(app.grid_t_start, app.units.temp), t_str),
+        ("5-Temperature Stop:", units::get_temperature(app.grid_t_stop, app.units.temp), t_str),
+        ("6-Temperature Step:", units::get_temperature_difference(app.grid_t_step, app.units.temp), t_str),
+    ];
+    let mut items: Vec<ListItem<'static>> = inputs
+        .iter()
+        .map(|(label, val, unit)| {
+            ListItem::new(
+                format!("{:<24} {:.4} {}",
+                    label, val, unit,
+                )
+            )
+                .fg(Color::LightYellow)
+                .bg(Color::Black)
+        })
+        .collect();
+    items.push(
+        ListItem::new(
+            format!("{:<24} {}",
+                "Output Format:", app.grid_format.name(),
+            )
+        )
+            .fg(Color::LightYellow)
+            .bg(Color::Black)
+    );
+
+    let result = grid(app).and_then(|grid| {
+        interpolation_errors(app, &grid).map(|errors| (grid, errors))
+    });
+    let (grid, errors) = match result {
+        Ok(result) => result,
+        Err(msg) => {
+            items.push(
+                ListItem::new(msg)
+                    .fg(Color::Red)
+                    .bg(Color::Black)
+            );
+            return items
+        }
+    };
+
+    items.push(
+        ListItem::new(
+            format!("{:<24} {} x {} ({})",
+                "Grid Size (P x T):", grid.pressure.len(), grid.temperature.len(), app.model.name(),
+            )
+        )
+            .fg(Color::White)
+            .bg(Color::Black)
+    );
+    items.push(
+        ListItem::new("Max bilinear lookup error at cell midpoints")
+            .fg(Color::White)
+            .bg(Color::Blue)
+    );
+    for (table, error) in grid.tables.iter().zip(errors.iter()) {
+        items.push(
+            ListItem::new(
+                format!("{:<24} {:.6} {} ({:.4} %) at {:.4} {}, {:.4} {}",
+                    format!("{}:", table.name), error.abs, table.unit, error.percent,
+                    error.p, p_str, error.t, t_str,
+                )
+            )
+                .fg(Color::LightCyan)
+                .bg(Color::Black)
+        );
+    }
+
+    if !app.grid_message.is_empty() {
+        items.push(
+            ListItem::new(app.grid_message.clone())
+                .fg(Color::White)
+                .bg(Color::Black)
+        );
+    }
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eos::Model;
+    use crate::gas::pure_component;
+
+    fn ideal_gas_app() -> App {
+        App {
+            gas_comp: pure_component(0),
+            model: Model::IdealGas,
+            ..Default::default()
+        }
+    }
+
+    // Ideal gas density is p/(R T) times a constant, so the midpoint error is
+    // (t2 - t1)^2 / (4 t1 t2) in the coldest column and Z is exact
+    #[test]
+    fn ideal_gas_midpoint_errors() {
+        let app = ideal_gas_app();
+        let grid = grid(&app).unwrap();
+        let errors = interpolation_errors(&app, &grid).unwrap();
+        let (t1, t2) = (app.grid_t_start, app.grid_t_start + app.grid_t_step);
+        let expected = (t2 - t1).powi(2) / (4.0 * t1 * t2) * 100.0;
+        assert!((errors[0].percent / expected - 1.0).abs() < 1.0e-9, "{} {}", errors[0].percent, expected);
+        assert!(errors[1].percent.abs() < 1.0e-12);
+    }
+
+    #[test]
+    fn axes_limits() {
+        let app = ideal_gas_app();
+        let (p, t) = axes(&app).unwrap();
+        assert_eq!((p.len(), t.len()), (10, 8));
+        let app = App {
+            grid_p_step: 1.0,
+            grid_t_step: 0.1,
+            ..ideal_gas_app()
+        };
+        assert!(axes(&app).is_err());
+        let app = App {
+            grid_p_start: 0.0,
+            ..ideal_gas_app()
+        };
+        assert!(axes(&app).is_err());
+    }
+}
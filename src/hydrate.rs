@@ -0,0 +1,115 @@
+use aga8::composition::Composition;
+
+use crate::{
+    App,
+    units,
+};
+
+// Molar mass of dry air used for gas gravity (g/mol)
+const AIR_MOLAR_MASS: f64 = 28.9625;
+// Gas gravity range of the Katz chart fit
+const MIN_GRAVITY: f64 = 0.55;
+const MAX_GRAVITY: f64 = 1.0;
+// Smallest hydrocarbon mole fraction the gas gravity method applies to
+const MIN_HYDROCARBON: f64 = 0.5;
+
+#[derive(Clone, Copy)]
+pub enum Inhibitor {
+    None,
+    Methanol,
+    EthyleneGlycol,
+}
+impl Inhibitor {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Inhibitor::None => "None",
+            Inhibitor::Methanol => "Methanol",
+            Inhibitor::EthyleneGlycol => "MEG",
+        }
+    }
+
+    // Hammerschmidt constant (F) and inhibitor molar mass (g/mol), GPSA values
+    fn hammerschmidt_constants(&self) -> (f64, f64) {
+        match self {
+            Inhibitor::None => (0.0, 1.0),
+            Inhibitor::Methanol => (2335.0, 32.04),
+            Inhibitor::EthyleneGlycol => (2200.0, 62.07),
+        }
+    }
+}
+
+pub fn gas_gravity(mm: f64) -> f64 {
+    mm / AIR_MOLAR_MASS
+}
+
+// Uninhibited hydrate formation temperature (K) from the Katz gas-gravity
+// chart, using the Motiee curve fit. Pressure in kPa.
+pub fn katz_hydrate_temp(p: f64, gravity: f64) -> f64 {
+    let p_psia = units::get_pressure(p, units::Pressure::PSI);
+    if p_psia <= 0.0 {
+        return 0.0
+    }
+    let log_p = p_psia.log10();
+    let t_f = -238.24469
+        + 78.99667 * log_p
+        - 5.352544 * log_p * log_p
+        + 349.473877 * gravity
+        - 150.854675 * gravity * gravity
+        - 27.604065 * log_p * gravity;
+    units::set_temperature(t_f, units::Temperature::F)
+}
+
+// Hydrate temperature depression (K) from the Hammerschmidt equation for the
+// inhibitor weight percent in the free water phase.
+pub fn hammerschmidt_depression(inhibitor: Inhibitor, wt_pct: f64) -> f64 {
+    if wt_pct <= 0.0 || wt_pct >= 100.0 {
+        return 0.0
+    }
+    let (k_h, m) = inhibitor.hammerschmidt_constants();
+    let dt_f = k_h * wt_pct / (m * (100.0 - wt_pct));
+    dt_f * 5.0 / 9.0
+}
+
+// Whether the gas gravity method applies: a hydrocarbon-dominated gas
+// inside the gravity range of the chart fit
+pub fn gravity_method_applies(gas_comp: &Composition, mm: f64) -> bool {
+    let gravity = gas_gravity(mm);
+    let hydrocarbons = gas_comp.methane
+        + gas_comp.ethane
+        + gas_comp.propane
+        + gas_comp.isobutane
+        + gas_comp.n_butane
+        + gas_comp.isopentane
+        + gas_comp.n_pentane
+        + gas_comp.hexane
+        + gas_comp.heptane
+        + gas_comp.octane
+        + gas_comp.nonane
+        + gas_comp.decane;
+    (MIN_GRAVITY..=MAX_GRAVITY).contains(&gravity) && hydrocarbons >= MIN_HYDROCARBON
+}
+
+// Hydrate formation temperature (K) of a gas with molar mass mm at pressure
+// p (kPa), none when the gas gravity method does not apply
+pub fn hydrate_temp_of(app: &App, gas_comp: &Composition, mm: f64, p: f64) -> Option<f64> {
+    if !gravity_method_applies(gas_comp, mm) {
+        return None
+    }
+    let t_hyd = katz_hydrate_temp(p, gas_gravity(mm));
+    Some(t_hyd - hammerschmidt_depression(app.inhibitor, app.inhibitor_wt))
+}
+
+// Hydrate formation temperature (K) of the active composition at pressure p (kPa).
+pub fn hydrate_temp(app: &App, p: f64) -> Option<f64> {
+    hydrate_temp_of(app, &app.gas_comp, app.cur_state.mm, p)
+}
+
+// Outlet temperature minus the hydrate formation temperature at outlet
+// pressure, in the selected temperature unit. Negative values are inside
+// the hydrate region.
+pub fn hydrate_margin(app: &App) -> Option<f64> {
+    let (p2, t2) = (app.outlet_state.p, app.outlet_state.t);
+    let t_hyd = units::get_temperature(hydrate_temp(app, p2)?, app.units.temp);
+    let t2 = units::get_temperature(t2, app.units.temp);
+    Some(t2 - t_hyd)
+}
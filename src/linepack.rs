@@ -0,0 +1,291 @@
+use std::f64::consts::PI;
+
+use ratatui::widgets::ListItem;
+use ratatui::
+    style::{
+        Color,
+        Stylize
+    };
+
+use crate::eos::new_eos;
+use crate::flash;
+use crate::gas::gross_heating_value;
+use crate::units::{
+    self,
+    PrintUnit,
+};
+use crate::App;
+
+// Standard volume base conditions, 15 C metric and 60 F imperial
+const BASE_P: f64 = 101.325;
+const BASE_T_METRIC: f64 = 288.15;
+const BASE_T_IMPERIAL: f64 = 288.706;
+// Simpson's rule intervals along the segment, must be even
+const INTERVALS: usize = 20;
+
+pub struct Linepack {
+    // Inventory mass in kg
+    pub mass: f64,
+    // Average pressure (kPa) and density (kg/m^3) over the segment
+    pub p_avg: f64,
+    pub rho_avg: f64,
+    pub mm: f64,
+    // Standard volume in m^3 at the base conditions
+    pub std_volume: f64,
+    // Gross energy content in GJ
+    pub energy: f64,
+}
+
+// Base temperature for the selected unit system
+fn base_temp(app: &App) -> f64 {
+    if app.units.flow.is_imperial() { BASE_T_IMPERIAL } else { BASE_T_METRIC }
+}
+
+// Standard volume (m^3) of a mass (kg) of the active gas at base conditions
+pub fn std_volume(app: &App, mass: f64) -> f64 {
+    let base = flash::props_pt(&app.gas_comp, app.model, BASE_P, base_temp(app));
+    mass / (base.d * base.mm)
+}
+
+// Gross energy content (GJ) of a mass (kg) of gas with molar mass mm (g/mol);
+// kmol times kJ/mol gives MJ
+pub fn energy_content(app: &App, mass: f64, mm: f64) -> f64 {
+    mass / mm * gross_heating_value(&app.gas_comp) / 1000.0
+}
+
+// Integrates the EOS density along the segment between the inlet and outlet
+// states, pressure following the steady flow square law and temperature
+// varying linearly
+pub fn linepack(app: &App) -> Linepack {
+    let (p1, t1, p2, t2) = (app.inlet_state.p, app.inlet_state.t, app.outlet_state.p, app.outlet_state.t);
+    let area = PI / 4.0 * app.pipeline_d * app.pipeline_d;
+
+    let mut rho_integral = 0.0;
+    let mut p_integral = 0.0;
+    let mut mm = 0.0;
+    let mut eos = new_eos(app.model, &app.gas_comp);
+    for i in 0..=INTERVALS {
+        let frac = i as f64 / INTERVALS as f64;
+        let p = (p1 * p1 - (p1 * p1 - p2 * p2) * frac).max(0.0).sqrt();
+        let t = t1 + (t2 - t1) * frac;
+        let props = eos.props_pt(p, t);
+        let weight = if i == 0 || i == INTERVALS {
+            1.0
+        } else if i % 2 == 1 {
+            4.0
+        } else {
+            2.0
+        };
+        rho_integral += weight * props.d * props.mm;
+        p_integral += weight * p;
+        mm = props.mm;
+    }
+    let rho_avg = rho_integral / (3.0 * INTERVALS as f64);
+    let p_avg = p_integral / (3.0 * INTERVALS as f64);
+    let mass = rho_avg * area * app.pipeline_length;
+
+    let std_volume = std_volume(app, mass);
+    let energy = energy_content(app, mass, mm);
+
+    Linepack {
+        mass,
+        p_avg,
+        rho_avg,
+        mm,
+        std_volume,
+        energy,
+    }
+}
+
+// Mass, standard volume and energy in the selected unit system
+pub fn inventory_units(app: &App, mass: f64, std_volume: f64, energy: f64) -> [(f64, &'static str); 3] {
+    if app.units.flow.is_imperial() {
+        [
+            (mass * 2.20462, "lbm"),
+            (std_volume * 35.3147 / 1.0e6, "MMscf"),
+            (energy / 1.055056, "MMBTU"),
+        ]
+    } else {
+        [
+            (mass / 1000.0, "tonne"),
+            (std_volume / 1.0e3, "10^3 Sm^3"),
+            (energy, "GJ"),
+        ]
+    }
+}
+
+// Stores the current inventory as the reference case
+pub fn save_case(app: &mut App) {
+    if app.show_inlet_state && app.show_outlet_state {
+        app.linepack_case = Some(linepack(app));
+    }
+}
+
+pub fn set_param(app: &mut App, val: f64) {
+    match app.input_param {
+        1 => app.pipeline_length = units::set_distance(val, app.units.length),
+        2 => app.pipeline_d = units::set_length(val, app.units.length),
+        _ => {}
+    }
+}
+
+pub fn run_linepack(app: &App) -> Vec<ListItem<'static>> {
+    let p_str = app.units.pressure.print_unit();
+
+    let mut items = vec![
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "1-Length:", units::get_distance(app.pipeline_length, app.units.length), app.units.length.print_distance_unit(),
+            )
+        )
+            .fg(Color::LightYellow)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "2-Inside Diameter:", units::get_length(app.pipeline_d, app.units.length), app.units.length.print_unit(),
+            )
+        )
+            .fg(Color::LightYellow)
+            .bg(Color::Black),
+    ];
+
+    if !(app.show_inlet_state && app.show_outlet_state) {
+        items.push(
+            ListItem::new("Set the segment inlet (I) and outlet (O) states")
+                .fg(Color::Red)
+                .bg(Color::Black)
+        );
+        return items
+    }
+
+    let pack = linepack(app);
+    let inventory = inventory_units(app, pack.mass, pack.std_volume, pack.energy);
+    items.push(
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Average Pressure:", units::get_pressure(pack.p_avg, app.units.pressure), p_str,
+            )
+        )
+            .fg(Color::White)
+            .bg(Color::Black)
+    );
+    items.push(
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Average Density:", units::get_density(pack.rho_avg / pack.mm, app.units.density, pack.mm), app.units.density.print_unit(),
+            )
+        )
+            .fg(Color::White)
+            .bg(Color::Black)
+    );
+    for (label, (val, unit)) in ["Linepack Mass:", "Linepack Std Volume:", "Linepack Energy:"].iter().zip(inventory.iter()) {
+        items.push(
+            ListItem::new(
+                format!("{:<24} {:.4} {}",
+                    label, val, unit,
+                )
+            )
+                .fg(Color::LightCyan)
+                .bg(Color::Black)
+        );
+    }
+
+    let case = match &app.linepack_case {
+        Some(case) => case,
+        None => {
+            items.push(
+                ListItem::new("S saves this case as the reference for packing/unpacking")
+                    .fg(Color::White)
+                    .bg(Color::Black)
+            );
+            return items
+        },
+    };
+    let reference = inventory_units(app, case.mass, case.std_volume, case.energy);
+    items.push(
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Reference Avg Press:", units::get_pressure(case.p_avg, app.units.pressure), p_str,
+            )
+        )
+            .fg(Color::White)
+            .bg(Color::Black)
+    );
+    for (label, ((val, unit), (ref_val, _))) in ["Change in Mass:", "Change in Std Volume:", "Change in Energy:"]
+        .iter()
+        .zip(inventory.iter().zip(reference.iter()))
+    {
+        let change = val - ref_val;
+        let color = if change < 0.0 { Color::LightRed } else { Color::LightGreen };
+        items.push(
+            ListItem::new(
+                format!("{:<24} {:+.4} {}",
+                    label, change, unit,
+                )
+            )
+                .fg(color)
+                .bg(Color::Black)
+        );
+    }
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eos::{
+        GasProps,
+        Model,
+        R,
+    };
+    use crate::gas::pure_component;
+
+    fn methane_line(model: Model, p1: f64, p2: f64) -> App {
+        App {
+            gas_comp: pure_component(0),
+            model,
+            inlet_state: GasProps {
+                p: p1,
+                t: 288.15,
+                ..Default::default()
+            },
+            outlet_state: GasProps {
+                p: p2,
+                t: 288.15,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    // Mean pressure of an isothermal steady flow line,
+    // 2/3 (p1 + p2 - p1 p2 / (p1 + p2)) (Mohitpour, Pipeline Design)
+    #[test]
+    fn mean_pressure() {
+        let (p1, p2) = (7000.0, 4000.0);
+        let result = linepack(&methane_line(Model::IdealGas, p1, p2));
+        let expected = 2.0 / 3.0 * (p1 + p2 - p1 * p2 / (p1 + p2));
+        assert!((result.p_avg / expected - 1.0).abs() < 1.0e-5, "{} {}", result.p_avg, expected);
+    }
+
+    // An ideal gas line holds the mean pressure density times the volume
+    #[test]
+    fn ideal_gas_mass() {
+        let (p1, p2) = (7000.0, 4000.0);
+        let app = methane_line(Model::IdealGas, p1, p2);
+        let result = linepack(&app);
+        let volume = PI / 4.0 * app.pipeline_d * app.pipeline_d * app.pipeline_length;
+        let rho = result.p_avg / (R * 288.15) * result.mm;
+        assert!((result.mass / (rho * volume) - 1.0).abs() < 1.0e-6);
+    }
+
+    // With no pressure drop the Simpson integral is the EOS density
+    #[test]
+    fn uniform_line() {
+        let app = methane_line(Model::Gerg2008, 5000.0, 5000.0);
+        let result = linepack(&app);
+        let props = flash::props_pt(&app.gas_comp, app.model, 5000.0, 288.15);
+        assert!((result.rho_avg / (props.d * props.mm) - 1.0).abs() < 1.0e-12);
+    }
+}
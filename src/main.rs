@@ -1,12 +1,36 @@
+mod blowdown;
 mod calculations;
+mod compare;
+mod corrector;
+mod cubic;
+mod departure;
+mod eos;
+mod exchanger;
+mod expander;
+mod filling;
+mod flash;
+mod fugacity;
 mod gas;
+mod grid;
+mod hydrate;
+mod linepack;
+mod mixer;
 mod modals;
+mod nozzle;
+mod orifice;
+mod pipeline;
+mod refstate;
+mod relief;
+mod storage;
+mod sweep;
+mod throttle;
+mod transport;
 mod units;
+mod usm;
+mod valve;
 
 use std::os::linux::raw::stat;
 
-use aga8::detail::Detail;
-use aga8::gerg2008::Gerg2008;
 use aga8::composition::Composition;
 
 use crossterm::event::{self, Event, KeyCode, KeyEventKind};
@@ -32,6 +56,10 @@ use ratatui::{
 };
 use ratatui_textarea::TextArea;
 
+use crate::eos::{
+    GasProps,
+    Model,
+};
 use crate::gas::{
     get_gas_comp, 
     set_gas
@@ -56,17 +84,41 @@ pub struct App {
     pub entropy_units_modal_visible: bool,
     pub speed_units_modal_visible: bool,
     pub flow_units_modal_visible: bool,
+    pub power_units_modal_visible: bool,
+    pub viscosity_units_modal_visible: bool,
+    pub conductivity_units_modal_visible: bool,
     pub input_speed_modal_visible: bool,
     pub gear_ratio_modal_visible: bool,
     pub wheel_diameter_modal_visible: bool,
-    pub aga8_cur_state: Detail,
-    pub gerg_cur_state: Gerg2008,
-    pub aga8_inlet_state: Detail,
-    pub gerg_inlet_state: Gerg2008,
-    pub aga8_outlet_state: Detail,
-    pub gerg_outlet_state: Gerg2008,
+    pub hydrate_modal_visible: bool,
+    pub inhibitor_modal_visible: bool,
+    pub tools_modal_visible: bool,
+    pub throttle_modal_visible: bool,
+    pub expander_modal_visible: bool,
+    pub mixer_modal_visible: bool,
+    pub exchanger_modal_visible: bool,
+    pub orifice_modal_visible: bool,
+    pub nozzle_modal_visible: bool,
+    pub usm_modal_visible: bool,
+    pub corrector_modal_visible: bool,
+    pub valve_modal_visible: bool,
+    pub relief_modal_visible: bool,
+    pub pipeline_modal_visible: bool,
+    pub linepack_modal_visible: bool,
+    pub blowdown_modal_visible: bool,
+    pub filling_modal_visible: bool,
+    pub storage_modal_visible: bool,
+    pub compare_modal_visible: bool,
+    pub departure_modal_visible: bool,
+    pub refstate_modal_visible: bool,
+    pub fugacity_modal_visible: bool,
+    pub sweep_modal_visible: bool,
+    pub grid_modal_visible: bool,
+    pub cur_state: GasProps,
+    pub inlet_state: GasProps,
+    pub outlet_state: GasProps,
     pub gas_comp: Composition,
-    pub use_gerg2008: bool,
+    pub model: Model,
     pub units: Units,
     pub show_inlet_state: bool,
     pub show_outlet_state: bool,
@@ -78,6 +130,94 @@ pub struct App {
     pub wheel_diameter: f64,
     pub gas_text: &'static str,
     pub stp_60_F: bool,
+    pub inhibitor: hydrate::Inhibitor,
+    pub inhibitor_wt: f64,
+    pub input_param: usize,
+    pub throttle_p2: f64,
+    pub expander_p2: f64,
+    pub expander_eff: f64,
+    pub expander_brake: bool,
+    pub mixer_streams: Vec<mixer::Stream>,
+    pub hx_utility_t_in: f64,
+    pub hx_utility_t_out: f64,
+    pub orifice_pipe_d: f64,
+    pub orifice_bore_d: f64,
+    pub orifice_dp: f64,
+    pub orifice_tap: orifice::Tap,
+    pub nozzle_throat_d: f64,
+    pub nozzle_cd: f64,
+    pub usm_paths: Vec<f64>,
+    pub usm_tolerance: f64,
+    pub usm_log: Vec<usm::Check>,
+    pub meter_volume: f64,
+    pub meter_base_p: f64,
+    pub meter_base_t: f64,
+    pub valve_p2: f64,
+    pub valve_xt: f64,
+    pub valve_fp: f64,
+    pub valve_pipe_d: f64,
+    pub relief_set_p: f64,
+    pub relief_overpressure: f64,
+    pub relief_t: f64,
+    pub relief_back_p: f64,
+    pub relief_kd: f64,
+    pub pipeline_length: f64,
+    pub pipeline_d: f64,
+    pub pipeline_roughness: f64,
+    pub pipeline_dz: f64,
+    pub pipeline_c_factor: f64,
+    pub pipeline_eq: pipeline::Equation,
+    pub linepack_case: Option<linepack::Linepack>,
+    pub blowdown_volume: f64,
+    pub blowdown_orifice_d: f64,
+    pub blowdown_cd: f64,
+    pub blowdown_back_p: f64,
+    pub blowdown_target_p: f64,
+    pub blowdown_wall_mass: f64,
+    pub blowdown_h: f64,
+    pub fill_volume: f64,
+    pub fill_p0: f64,
+    pub fill_t0: f64,
+    pub fill_target_p: f64,
+    pub fill_rate: f64,
+    pub fill_orifice_d: f64,
+    pub fill_cd: f64,
+    pub fill_wall_mass: f64,
+    pub fill_h: f64,
+    pub fill_use_orifice: bool,
+    pub storage_volume: f64,
+    pub storage_count: f64,
+    pub storage_p_max: f64,
+    pub storage_p_min: f64,
+    pub storage_target: f64,
+    pub compare_threshold: f64,
+    pub compare_scroll: usize,
+    pub compare_model: Model,
+    pub departure_scroll: usize,
+    pub ref_state: refstate::RefState,
+    pub ref_p: f64,
+    pub ref_t: f64,
+    pub ref_offset: refstate::Offset,
+    pub fugacity_scroll: usize,
+    pub sweep_variable: sweep::Variable,
+    pub sweep_start: f64,
+    pub sweep_stop: f64,
+    pub sweep_step: f64,
+    pub sweep_props: [bool; 23],
+    pub sweep_cursor: usize,
+    pub sweep_scroll: usize,
+    pub sweep_message: String,
+    pub grid_p_start: f64,
+    pub grid_p_stop: f64,
+    pub grid_p_step: f64,
+    pub grid_t_start: f64,
+    pub grid_t_stop: f64,
+    pub grid_t_step: f64,
+    pub grid_format: grid::Format,
+    pub grid_message: String,
+    // Tool modal results, cleared after any event that can change an input
+    pub tool_items: Option<Vec<ListItem<'static>>>,
+    pub sweep_result: Option<Result<sweep::Sweep, &'static str>>,
 }
 
 impl Default for App {
@@ -95,17 +235,41 @@ impl Default for App {
             entropy_units_modal_visible: false,
             speed_units_modal_visible: false,
             flow_units_modal_visible: false,
+            power_units_modal_visible: false,
+            viscosity_units_modal_visible: false,
+            conductivity_units_modal_visible: false,
             input_speed_modal_visible: false,
             gear_ratio_modal_visible: false,
             wheel_diameter_modal_visible: false,
-            aga8_cur_state: Detail::new(),
-            gerg_cur_state: Gerg2008::new(), 
-            aga8_inlet_state: Detail::new(),
-            gerg_inlet_state: Gerg2008::new(),
-            aga8_outlet_state: Detail::new(),
-            gerg_outlet_state: Gerg2008::new(),
+            hydrate_modal_visible: false,
+            inhibitor_modal_visible: false,
+            tools_modal_visible: false,
+            throttle_modal_visible: false,
+            expander_modal_visible: false,
+            mixer_modal_visible: false,
+            exchanger_modal_visible: false,
+            orifice_modal_visible: false,
+            nozzle_modal_visible: false,
+            usm_modal_visible: false,
+            corrector_modal_visible: false,
+            valve_modal_visible: false,
+            relief_modal_visible: false,
+            pipeline_modal_visible: false,
+            linepack_modal_visible: false,
+            blowdown_modal_visible: false,
+            filling_modal_visible: false,
+            storage_modal_visible: false,
+            compare_modal_visible: false,
+            departure_modal_visible: false,
+            refstate_modal_visible: false,
+            fugacity_modal_visible: false,
+            sweep_modal_visible: false,
+            grid_modal_visible: false,
+            cur_state: GasProps::default(),
+            inlet_state: GasProps::default(),
+            outlet_state: GasProps::default(),
             gas_comp: Composition::default(),
-            use_gerg2008: true,
+            model: Model::Gerg2008,
             units: Units::default(),
             show_inlet_state: false,
             show_outlet_state: false,
@@ -117,52 +281,114 @@ impl Default for App {
             wheel_diameter: 0.0,
             gas_text: "Air",
             stp_60_F: true,
+            inhibitor: hydrate::Inhibitor::None,
+            inhibitor_wt: 0.0,
+            input_param: 0,
+            throttle_p2: 101.325,
+            expander_p2: 101.325,
+            expander_eff: 0.85,
+            expander_brake: false,
+            mixer_streams: Vec::new(),
+            hx_utility_t_in: 0.0,
+            hx_utility_t_out: 0.0,
+            orifice_pipe_d: 0.1023,
+            orifice_bore_d: 0.05,
+            orifice_dp: 25.0,
+            orifice_tap: orifice::Tap::Flange,
+            nozzle_throat_d: 0.01,
+            nozzle_cd: 0.995,
+            usm_paths: Vec::new(),
+            usm_tolerance: 0.2,
+            usm_log: Vec::new(),
+            meter_volume: 0.0,
+            meter_base_p: 101.325,
+            meter_base_t: 288.706,
+            valve_p2: 101.325,
+            valve_xt: 0.72,
+            valve_fp: 1.0,
+            valve_pipe_d: 0.1023,
+            relief_set_p: 0.0,
+            relief_overpressure: 10.0,
+            relief_t: 0.0,
+            relief_back_p: 101.325,
+            relief_kd: 0.975,
+            pipeline_length: 10000.0,
+            pipeline_d: 0.2545,
+            pipeline_roughness: 4.57e-5,
+            pipeline_dz: 0.0,
+            pipeline_c_factor: 100.0,
+            pipeline_eq: pipeline::Equation::Segmented,
+            linepack_case: None,
+            blowdown_volume: 10.0,
+            blowdown_orifice_d: 0.025,
+            blowdown_cd: 0.85,
+            blowdown_back_p: 101.325,
+            blowdown_target_p: 791.325,
+            blowdown_wall_mass: 0.0,
+            blowdown_h: 20.0,
+            fill_volume: 0.15,
+            fill_p0: 2000.0,
+            fill_t0: 293.15,
+            fill_target_p: 70000.0,
+            fill_rate: 0.01,
+            fill_orifice_d: 0.004,
+            fill_cd: 0.85,
+            fill_wall_mass: 0.0,
+            fill_h: 20.0,
+            fill_use_orifice: false,
+            storage_volume: 0.05,
+            storage_count: 1.0,
+            storage_p_max: 20000.0,
+            storage_p_min: 1000.0,
+            storage_target: 0.0,
+            compare_threshold: 0.1,
+            compare_scroll: 0,
+            compare_model: Model::Detail,
+            departure_scroll: 0,
+            ref_state: refstate::RefState::Native,
+            ref_p: 101.325,
+            ref_t: 298.15,
+            ref_offset: refstate::Offset::default(),
+            fugacity_scroll: 0,
+            sweep_variable: sweep::Variable::Pressure,
+            sweep_start: 100.0,
+            sweep_stop: 10000.0,
+            sweep_step: 500.0,
+            sweep_props: sweep::DEFAULT_PROPERTIES,
+            sweep_cursor: 0,
+            sweep_scroll: 0,
+            sweep_message: String::new(),
+            grid_p_start: 1000.0,
+            grid_p_stop: 10000.0,
+            grid_p_step: 1000.0,
+            grid_t_start: 260.0,
+            grid_t_stop: 330.0,
+            grid_t_step: 10.0,
+            grid_format: grid::Format::Csv,
+            grid_message: String::new(),
+            tool_items: None,
+            sweep_result: None,
         }
     }
 }
 
 fn app_setup(app: &mut App) {
     app.gas_comp = get_gas_comp(gas::Gas::Air);
-    app.aga8_cur_state.set_composition(&app.gas_comp);
-    app.gerg_cur_state.set_composition(&app.gas_comp);
-    app.aga8_inlet_state.set_composition(&app.gas_comp);
-    app.gerg_inlet_state.set_composition(&app.gas_comp);
-    app.aga8_outlet_state.set_composition(&app.gas_comp);
-    app.gerg_outlet_state.set_composition(&app.gas_comp);
 
     let p = 14.696;
     let p = units::set_pressure(p, units::Pressure::PSI);
     let t = 60.0;
     let t = units::set_temperature(t, units::Temperature::F);
 
-    app.aga8_cur_state.p = p;
-    app.gerg_cur_state.p = p;
-    app.aga8_inlet_state.p = p;
-    app.gerg_inlet_state.p = p;
-    app.aga8_outlet_state.p = p;
-    app.gerg_outlet_state.p = p;
-
-    app.aga8_cur_state.t = t;
-    app.gerg_cur_state.t = t;
-    app.aga8_inlet_state.t = t;
-    app.gerg_inlet_state.t = t;
-    app.aga8_outlet_state.t = t;
-    app.gerg_outlet_state.t = t;
+    app.cur_state.p = p;
+    app.inlet_state.p = p;
+    app.outlet_state.p = p;
 
-    app.aga8_cur_state.density();
-    app.gerg_cur_state.density(0);
-    app.aga8_cur_state.properties();
-    app.gerg_cur_state.properties();
+    app.cur_state.t = t;
+    app.inlet_state.t = t;
+    app.outlet_state.t = t;
 
-    app.aga8_inlet_state.density();
-    app.gerg_inlet_state.density(0);
-    app.aga8_inlet_state.properties();
-    app.gerg_inlet_state.properties();
-
-    app.aga8_outlet_state.density();
-    app.gerg_outlet_state.density(0);
-    app.aga8_outlet_state.properties();
-    app.gerg_outlet_state.properties();
+    recalculate(app);
 }
 
 
@@ -179,42 +405,38 @@ fn main() -> std::io::Result<()> {
 fn run(terminal: &mut ratatui::DefaultTerminal, app: &mut App) -> std::io::Result<()> {
     loop {
         terminal.draw(|frame| draw(frame, app))?;
+        // Typing into the value prompt changes no tool input, so the tool
+        // results are kept until the value is entered
+        let typing = app.input_modal_active;
         if handle_events(app)? {
             break Ok(());
         }
+        if !(typing && app.input_modal_active) {
+            app.tool_items = None;
+            app.sweep_result = None;
+        }
     }
 }
 
-fn hotkey_menu(app: &mut App) -> Paragraph<'static> {
-    let mode_text;
-    if app.use_gerg2008 {
-        mode_text = "AGA8"
-    } else {
-        mode_text = "GERG-2008"
-    }
+fn hotkey_menu() -> Paragraph<'static> {
     Paragraph::new(
-        format!("Esc-Settings\tP-Pressure\tT-Temperature\tU-Change Units\tI-Set Inlet\tO- Set Outlet\tC-Clear\tM-Switch AGA8/GERG")
+        format!("Esc-Settings\tP-Pressure\tT-Temperature\tU-Change Units\tI-Set Inlet\tO- Set Outlet\tC-Clear\tM-Switch EOS\tH-Hydrate Inhibitor\tA-Analysis Tools")
     )
 }
 
 fn draw(frame: &mut Frame, app: &mut App) {
     use Constraint::{Fill, Length, Min};
 
-    let vertical = Layout::vertical([Length(1), Length(16), Fill(1), Length(3)]);
+    let vertical = Layout::vertical([Length(1), Length(26), Fill(1), Length(3)]);
     let [title_area, main_area, calc_area, status_area] = vertical.areas(frame.area());
     let horizontal = Layout::horizontal([Fill(1); 3]);
     let [left_area, center_area, right_area] = horizontal.areas(main_area);
     let [left_calc_area, center_calc_area, right_calc_area] = horizontal.areas(calc_area);
     
-    let gas_mode;
-    if app.use_gerg2008 {
-        gas_mode = "GERG-2008"
-    } else {
-        gas_mode = "AGA8"
-    }
+    let gas_mode = app.model.name();
     frame.render_widget(Block::bordered().title(format!("Thermodynamic Gas Calculator - {}", gas_mode)).style(Color::LightCyan), title_area);
     
-    let hotkey_par = hotkey_menu(app)
+    let hotkey_par = hotkey_menu()
         .block(
             Block::bordered()
             .title("Hotkeys")
@@ -346,6 +568,15 @@ fn draw(frame: &mut Frame, app: &mut App) {
     if app.flow_units_modal_visible {
         modals::flow_units_modal(app, frame, main_area);
     }
+    if app.power_units_modal_visible {
+        modals::power_units_modal(app, frame, main_area);
+    }
+    if app.viscosity_units_modal_visible {
+        modals::viscosity_units_modal(app, frame, main_area);
+    }
+    if app.conductivity_units_modal_visible {
+        modals::conductivity_units_modal(app, frame, main_area);
+    }
     if app.input_speed_modal_visible {
         modals::input_speed_modal(app, frame, main_area);
     }
@@ -355,6 +586,79 @@ fn draw(frame: &mut Frame, app: &mut App) {
     if app.wheel_diameter_modal_visible {
         modals::wheel_diameter_modal(app, frame, main_area);
     }
+    if app.hydrate_modal_visible {
+        modals::hydrate_modal(app, frame, main_area);
+    }
+    if app.inhibitor_modal_visible {
+        modals::inhibitor_modal(app, frame, main_area);
+    }
+    if app.tools_modal_visible {
+        modals::tools_modal(app, frame, main_area);
+    }
+    let full_area = frame.area();
+    if app.throttle_modal_visible {
+        modals::throttle_modal(app, frame, full_area);
+    }
+    if app.expander_modal_visible {
+        modals::expander_modal(app, frame, full_area);
+    }
+    if app.mixer_modal_visible {
+        modals::mixer_modal(app, frame, full_area);
+    }
+    if app.exchanger_modal_visible {
+        modals::exchanger_modal(app, frame, full_area);
+    }
+    if app.orifice_modal_visible {
+        modals::orifice_modal(app, frame, full_area);
+    }
+    if app.nozzle_modal_visible {
+        modals::nozzle_modal(app, frame, full_area);
+    }
+    if app.usm_modal_visible {
+        modals::usm_modal(app, frame, full_area);
+    }
+    if app.corrector_modal_visible {
+        modals::corrector_modal(app, frame, full_area);
+    }
+    if app.valve_modal_visible {
+        modals::valve_modal(app, frame, full_area);
+    }
+    if app.relief_modal_visible {
+        modals::relief_modal(app, frame, full_area);
+    }
+    if app.pipeline_modal_visible {
+        modals::pipeline_modal(app, frame, full_area);
+    }
+    if app.linepack_modal_visible {
+        modals::linepack_modal(app, frame, full_area);
+    }
+    if app.blowdown_modal_visible {
+        modals::blowdown_modal(app, frame, full_area);
+    }
+    if app.filling_modal_visible {
+        modals::filling_modal(app, frame, full_area);
+    }
+    if app.storage_modal_visible {
+        modals::storage_modal(app, frame, full_area);
+    }
+    if app.compare_modal_visible {
+        modals::compare_modal(app, frame, full_area);
+    }
+    if app.departure_modal_visible {
+        modals::departure_modal(app, frame, full_area);
+    }
+    if app.refstate_modal_visible {
+        modals::refstate_modal(app, frame, full_area);
+    }
+    if app.fugacity_modal_visible {
+        modals::fugacity_modal(app, frame, full_area);
+    }
+    if app.sweep_modal_visible {
+        modals::sweep_modal(app, frame, full_area);
+    }
+    if app.grid_modal_visible {
+        modals::grid_modal(app, frame, full_area);
+    }
 }
 
 fn handle_events(app: &mut App) -> std::io::Result<bool> {
@@ -378,6 +682,44 @@ fn handle_events(app: &mut App) -> std::io::Result<bool> {
                         app.gear_ratio = val;
                     } else if app.wheel_diameter_modal_visible {
                         app.wheel_diameter = val;
+                    } else if app.inhibitor_modal_visible {
+                        app.inhibitor_wt = val;
+                    } else if app.throttle_modal_visible {
+                        throttle::set_param(app, val);
+                    } else if app.expander_modal_visible {
+                        expander::set_param(app, val);
+                    } else if app.exchanger_modal_visible {
+                        exchanger::set_param(app, val);
+                    } else if app.orifice_modal_visible {
+                        orifice::set_param(app, val);
+                    } else if app.nozzle_modal_visible {
+                        nozzle::set_param(app, val);
+                    } else if app.usm_modal_visible {
+                        usm::set_param(app, val);
+                    } else if app.corrector_modal_visible {
+                        corrector::set_param(app, val);
+                    } else if app.valve_modal_visible {
+                        valve::set_param(app, val);
+                    } else if app.relief_modal_visible {
+                        relief::set_param(app, val);
+                    } else if app.pipeline_modal_visible {
+                        pipeline::set_param(app, val);
+                    } else if app.linepack_modal_visible {
+                        linepack::set_param(app, val);
+                    } else if app.blowdown_modal_visible {
+                        blowdown::set_param(app, val);
+                    } else if app.filling_modal_visible {
+                        filling::set_param(app, val);
+                    } else if app.storage_modal_visible {
+                        storage::set_param(app, val);
+                    } else if app.compare_modal_visible {
+                        compare::set_param(app, val);
+                    } else if app.refstate_modal_visible {
+                        refstate::set_param(app, val);
+                    } else if app.sweep_modal_visible {
+                        sweep::set_param(app, val);
+                    } else if app.grid_modal_visible {
+                        grid::set_param(app, val);
                     }
                 }
                 app.input_modal_active = false;
@@ -387,6 +729,7 @@ fn handle_events(app: &mut App) -> std::io::Result<bool> {
                 app.input_speed_modal_visible = false;
                 app.gear_ratio_modal_visible = false;
                 app.wheel_diameter_modal_visible = false;
+                app.inhibitor_modal_visible = false;
                 app.input_text = TextArea::default();
             },
             KeyCode::Esc => {
@@ -397,91 +740,818 @@ fn handle_events(app: &mut App) -> std::io::Result<bool> {
                 app.input_speed_modal_visible = false;
                 app.gear_ratio_modal_visible = false;
                 app.wheel_diameter_modal_visible = false;
+                app.inhibitor_modal_visible = false;
                 app.input_text = TextArea::default();
             },
-            KeyCode::Backspace => {
-                app.input_text.delete_char();
+            KeyCode::Backspace => {
+                app.input_text.delete_char();
+            },
+            KeyCode::Char('u') => {
+                if app.pressure_modal_visible {
+                    app.pressure_units_modal_visible = true;
+                    app.pressure_modal_visible = false;
+                    app.input_modal_active = false;
+                } else if app.temperature_modal_visible {
+                    app.temperature_units_modal_visible = true;
+                    app.temperature_modal_visible = false;
+                    app.input_modal_active = false;
+                } else if app.flow_modal_visible {
+                    app.flow_units_modal_visible = true;
+                    app.flow_modal_visible = false;
+                    app.input_modal_active = false;
+                }
+            },
+            _ =>{
+                    let c = key.code.as_char();
+                    if c.is_some() {
+                        let c = c.unwrap();
+                        if c.is_numeric() || c == '.' || c == '-' {
+                            app.input_text.insert_char(c);
+                        }
+                    }
+            },
+            },
+            _ => {}
+        }
+        Ok(false)
+    } else if app.gas_modal_visible {
+        match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Enter => {
+                    app.gas_modal_visible = false;
+                },
+                KeyCode::Esc => {
+                    app.gas_modal_visible = false;
+                },
+                KeyCode::Char('1') => {
+                    set_gas(app, get_gas_comp(gas::Gas::Air));
+                    app.gas_text = "Air";
+                    app.gas_modal_visible = false;
+                },
+                KeyCode::Char('2') => {
+                    set_gas(app, get_gas_comp(gas::Gas::Argon));
+                    app.gas_text = "Argon";
+                    app.gas_modal_visible = false;
+                },
+                KeyCode::Char('3') => {
+                    set_gas(app, get_gas_comp(gas::Gas::CO));
+                    app.gas_text = "Carbon Monoxide";
+                    app.gas_modal_visible = false;
+                },
+                KeyCode::Char('4') => {
+                    set_gas(app, get_gas_comp(gas::Gas::CO2));
+                    app.gas_text = "Carbon Dioxide";
+                    app.gas_modal_visible = false;
+                },
+                KeyCode::Char('5') => {
+                    set_gas(app, get_gas_comp(gas::Gas::Helium));
+                    app.gas_text = "Helium";
+                    app.gas_modal_visible = false;
+                },
+                KeyCode::Char('6') => {
+                    set_gas(app, get_gas_comp(gas::Gas::Hydrogen));
+                    app.gas_text = "Hydrogen";
+                    app.gas_modal_visible = false;
+                },
+                KeyCode::Char('7') => {
+                    set_gas(app, get_gas_comp(gas::Gas::Nitrogen));
+                    app.gas_text = "Nitrogen";
+                    app.gas_modal_visible = false;
+                },
+                KeyCode::Char('8') => {
+                    set_gas(app, get_gas_comp(gas::Gas::Oxygen));
+                    app.gas_text = "Oxygen";
+                    app.gas_modal_visible = false;
+                },
+                KeyCode::Char('9') => {
+                    app.gas_text = "Custom";
+                    app.gas_modal_visible = false;
+                },
+                _ =>{},
+            },
+            _ => {}
+        }
+        Ok(false)
+    } else if app.hydrate_modal_visible {
+        match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Enter => {
+                    app.hydrate_modal_visible = false;
+                },
+                KeyCode::Esc => {
+                    app.hydrate_modal_visible = false;
+                },
+                KeyCode::Char('1') => {
+                    app.inhibitor = hydrate::Inhibitor::None;
+                    app.inhibitor_wt = 0.0;
+                    app.hydrate_modal_visible = false;
+                },
+                KeyCode::Char('2') => {
+                    app.inhibitor = hydrate::Inhibitor::Methanol;
+                    app.hydrate_modal_visible = false;
+                    app.inhibitor_modal_visible = true;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('3') => {
+                    app.inhibitor = hydrate::Inhibitor::EthyleneGlycol;
+                    app.hydrate_modal_visible = false;
+                    app.inhibitor_modal_visible = true;
+                    app.input_modal_active = true;
+                },
+                _ =>{},
+            },
+            _ => {}
+        }
+        Ok(false)
+    } else if app.tools_modal_visible {
+        match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Enter => {
+                    app.tools_modal_visible = false;
+                },
+                KeyCode::Esc => {
+                    app.tools_modal_visible = false;
+                },
+                KeyCode::Char('1') => {
+                    app.tools_modal_visible = false;
+                    app.throttle_modal_visible = true;
+                },
+                KeyCode::Char('2') => {
+                    app.tools_modal_visible = false;
+                    app.expander_modal_visible = true;
+                },
+                KeyCode::Char('3') => {
+                    app.tools_modal_visible = false;
+                    app.mixer_modal_visible = true;
+                },
+                KeyCode::Char('4') => {
+                    app.tools_modal_visible = false;
+                    app.exchanger_modal_visible = true;
+                },
+                KeyCode::Char('5') => {
+                    app.tools_modal_visible = false;
+                    app.orifice_modal_visible = true;
+                },
+                KeyCode::Char('6') => {
+                    app.tools_modal_visible = false;
+                    app.nozzle_modal_visible = true;
+                },
+                KeyCode::Char('7') => {
+                    app.tools_modal_visible = false;
+                    app.usm_modal_visible = true;
+                },
+                KeyCode::Char('8') => {
+                    app.tools_modal_visible = false;
+                    app.corrector_modal_visible = true;
+                },
+                KeyCode::Char('9') => {
+                    app.tools_modal_visible = false;
+                    app.valve_modal_visible = true;
+                },
+                KeyCode::Char('r') => {
+                    app.tools_modal_visible = false;
+                    app.relief_modal_visible = true;
+                },
+                KeyCode::Char('p') => {
+                    app.tools_modal_visible = false;
+                    app.pipeline_modal_visible = true;
+                },
+                KeyCode::Char('l') => {
+                    app.tools_modal_visible = false;
+                    app.linepack_modal_visible = true;
+                },
+                KeyCode::Char('b') => {
+                    app.tools_modal_visible = false;
+                    app.blowdown_modal_visible = true;
+                },
+                KeyCode::Char('f') => {
+                    app.tools_modal_visible = false;
+                    app.filling_modal_visible = true;
+                },
+                KeyCode::Char('s') => {
+                    app.tools_modal_visible = false;
+                    app.storage_modal_visible = true;
+                },
+                KeyCode::Char('e') => {
+                    app.tools_modal_visible = false;
+                    app.compare_modal_visible = true;
+                },
+                KeyCode::Char('D') => {
+                    app.tools_modal_visible = false;
+                    app.departure_modal_visible = true;
+                },
+                KeyCode::Char('H') => {
+                    app.tools_modal_visible = false;
+                    app.refstate_modal_visible = true;
+                },
+                KeyCode::Char('G') => {
+                    app.tools_modal_visible = false;
+                    app.fugacity_modal_visible = true;
+                },
+                KeyCode::Char('T') => {
+                    app.tools_modal_visible = false;
+                    app.sweep_modal_visible = true;
+                },
+                KeyCode::Char('K') => {
+                    app.tools_modal_visible = false;
+                    app.grid_modal_visible = true;
+                },
+                _ =>{},
+            },
+            _ => {}
+        }
+        Ok(false)
+    } else if app.throttle_modal_visible {
+        match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Esc => {
+                    app.throttle_modal_visible = false;
+                },
+                KeyCode::Char('1') => {
+                    app.input_param = 1;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('o') => {
+                    throttle::set_outlet(app);
+                    app.throttle_modal_visible = false;
+                },
+                _ =>{},
+            },
+            _ => {}
+        }
+        Ok(false)
+    } else if app.expander_modal_visible {
+        match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Esc => {
+                    app.expander_modal_visible = false;
+                },
+                KeyCode::Char('1') => {
+                    app.input_param = 1;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('2') => {
+                    app.input_param = 2;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('b') => {
+                    app.expander_brake = ! app.expander_brake;
+                },
+                _ =>{},
+            },
+            _ => {}
+        }
+        Ok(false)
+    } else if app.mixer_modal_visible {
+        match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Esc => {
+                    app.mixer_modal_visible = false;
+                },
+                KeyCode::Char('a') => {
+                    mixer::add_stream(app);
+                },
+                KeyCode::Char('d') => {
+                    app.mixer_streams.pop();
+                },
+                KeyCode::Char('c') => {
+                    app.mixer_streams.clear();
+                },
+                KeyCode::Char('l') => {
+                    mixer::load_mixture(app);
+                    app.mixer_modal_visible = false;
+                },
+                _ =>{},
+            },
+            _ => {}
+        }
+        Ok(false)
+    } else if app.exchanger_modal_visible {
+        match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Esc => {
+                    app.exchanger_modal_visible = false;
+                },
+                KeyCode::Char('1') => {
+                    app.input_param = 1;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('2') => {
+                    app.input_param = 2;
+                    app.input_modal_active = true;
+                },
+                _ =>{},
+            },
+            _ => {}
+        }
+        Ok(false)
+    } else if app.orifice_modal_visible {
+        match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Esc => {
+                    app.orifice_modal_visible = false;
+                },
+                KeyCode::Char('1') => {
+                    app.input_param = 1;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('2') => {
+                    app.input_param = 2;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('3') => {
+                    app.input_param = 3;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('t') => {
+                    app.orifice_tap = app.orifice_tap.next();
+                },
+                _ =>{},
+            },
+            _ => {}
+        }
+        Ok(false)
+    } else if app.nozzle_modal_visible {
+        match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Esc => {
+                    app.nozzle_modal_visible = false;
+                },
+                KeyCode::Char('1') => {
+                    app.input_param = 1;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('2') => {
+                    app.input_param = 2;
+                    app.input_modal_active = true;
+                },
+                _ =>{},
+            },
+            _ => {}
+        }
+        Ok(false)
+    } else if app.usm_modal_visible {
+        match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Esc => {
+                    app.usm_modal_visible = false;
+                },
+                KeyCode::Char('1') => {
+                    app.input_param = 1;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('2') => {
+                    app.input_param = 2;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('d') => {
+                    app.usm_paths.pop();
+                },
+                KeyCode::Char('c') => {
+                    app.usm_paths.clear();
+                },
+                KeyCode::Char('l') => {
+                    usm::log_check(app);
+                },
+                KeyCode::Char('x') => {
+                    app.usm_log.clear();
+                },
+                _ =>{},
+            },
+            _ => {}
+        }
+        Ok(false)
+    } else if app.corrector_modal_visible {
+        match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Esc => {
+                    app.corrector_modal_visible = false;
+                },
+                KeyCode::Char('1') => {
+                    app.input_param = 1;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('2') => {
+                    app.input_param = 2;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('3') => {
+                    app.input_param = 3;
+                    app.input_modal_active = true;
+                },
+                _ =>{},
+            },
+            _ => {}
+        }
+        Ok(false)
+    } else if app.valve_modal_visible {
+        match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Esc => {
+                    app.valve_modal_visible = false;
+                },
+                KeyCode::Char('1') => {
+                    app.input_param = 1;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('2') => {
+                    app.input_param = 2;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('3') => {
+                    app.input_param = 3;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('4') => {
+                    app.input_param = 4;
+                    app.input_modal_active = true;
+                },
+                _ =>{},
+            },
+            _ => {}
+        }
+        Ok(false)
+    } else if app.relief_modal_visible {
+        match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Esc => {
+                    app.relief_modal_visible = false;
+                },
+                KeyCode::Char('1') => {
+                    app.input_param = 1;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('2') => {
+                    app.input_param = 2;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('3') => {
+                    app.input_param = 3;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('4') => {
+                    app.input_param = 4;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('5') => {
+                    app.input_param = 5;
+                    app.input_modal_active = true;
+                },
+                _ =>{},
+            },
+            _ => {}
+        }
+        Ok(false)
+    } else if app.pipeline_modal_visible {
+        match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Esc => {
+                    app.pipeline_modal_visible = false;
+                },
+                KeyCode::Char('1') => {
+                    app.input_param = 1;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('2') => {
+                    app.input_param = 2;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('3') => {
+                    app.input_param = 3;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('4') => {
+                    app.input_param = 4;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('5') => {
+                    app.input_param = 5;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('e') => {
+                    app.pipeline_eq = app.pipeline_eq.next();
+                },
+                _ =>{},
+            },
+            _ => {}
+        }
+        Ok(false)
+    } else if app.linepack_modal_visible {
+        match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Esc => {
+                    app.linepack_modal_visible = false;
+                },
+                KeyCode::Char('1') => {
+                    app.input_param = 1;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('2') => {
+                    app.input_param = 2;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('s') => {
+                    linepack::save_case(app);
+                },
+                KeyCode::Char('x') => {
+                    app.linepack_case = None;
+                },
+                _ =>{},
+            },
+            _ => {}
+        }
+        Ok(false)
+    } else if app.blowdown_modal_visible {
+        match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Esc => {
+                    app.blowdown_modal_visible = false;
+                },
+                KeyCode::Char('1') => {
+                    app.input_param = 1;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('2') => {
+                    app.input_param = 2;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('3') => {
+                    app.input_param = 3;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('4') => {
+                    app.input_param = 4;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('5') => {
+                    app.input_param = 5;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('6') => {
+                    app.input_param = 6;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('7') => {
+                    app.input_param = 7;
+                    app.input_modal_active = true;
+                },
+                _ =>{},
+            },
+            _ => {}
+        }
+        Ok(false)
+    } else if app.filling_modal_visible {
+        match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Esc => {
+                    app.filling_modal_visible = false;
+                },
+                KeyCode::Char('1') => {
+                    app.input_param = 1;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('2') => {
+                    app.input_param = 2;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('3') => {
+                    app.input_param = 3;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('4') => {
+                    app.input_param = 4;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('5') => {
+                    app.input_param = 5;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('6') => {
+                    app.input_param = 6;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('7') => {
+                    app.input_param = 7;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('8') => {
+                    app.input_param = 8;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('9') => {
+                    app.input_param = 9;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('r') => {
+                    app.fill_use_orifice = ! app.fill_use_orifice;
+                },
+                _ =>{},
+            },
+            _ => {}
+        }
+        Ok(false)
+    } else if app.storage_modal_visible {
+        match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Esc => {
+                    app.storage_modal_visible = false;
+                },
+                KeyCode::Char('1') => {
+                    app.input_param = 1;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('2') => {
+                    app.input_param = 2;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('3') => {
+                    app.input_param = 3;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('4') => {
+                    app.input_param = 4;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('5') => {
+                    app.input_param = 5;
+                    app.input_modal_active = true;
+                },
+                _ =>{},
+            },
+            _ => {}
+        }
+        Ok(false)
+    } else if app.compare_modal_visible {
+        match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Esc => {
+                    app.compare_modal_visible = false;
+                    app.compare_scroll = 0;
+                },
+                KeyCode::Char('1') => {
+                    app.input_param = 1;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('m') => {
+                    app.compare_model = compare::next_model(app.compare_model);
+                },
+                KeyCode::Up => {
+                    app.compare_scroll = app.compare_scroll.saturating_sub(1);
+                },
+                KeyCode::Down => {
+                    app.compare_scroll += 1;
+                },
+                _ =>{},
             },
-            KeyCode::Char('u') => {
-                if app.pressure_modal_visible {
-                    app.pressure_units_modal_visible = true;
-                    app.pressure_modal_visible = false;
-                    app.input_modal_active = false;
-                } else if app.temperature_modal_visible {
-                    app.temperature_units_modal_visible = true;
-                    app.temperature_modal_visible = false;
-                    app.input_modal_active = false;
-                } else if app.flow_modal_visible {
-                    app.flow_units_modal_visible = true;
-                    app.flow_modal_visible = false;
-                    app.input_modal_active = false;
-                }
+            _ => {}
+        }
+        Ok(false)
+    } else if app.departure_modal_visible {
+        match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Esc => {
+                    app.departure_modal_visible = false;
+                    app.departure_scroll = 0;
+                },
+                KeyCode::Up => {
+                    app.departure_scroll = app.departure_scroll.saturating_sub(1);
+                },
+                KeyCode::Down => {
+                    app.departure_scroll += 1;
+                },
+                _ =>{},
             },
-            _ =>{
-                    let c = key.code.as_char();
-                    if c.is_some() {
-                        let c = c.unwrap();
-                        if c.is_numeric() || c == '.' || c == '-' {
-                            app.input_text.insert_char(c);
-                        }
-                    }
+            _ => {}
+        }
+        Ok(false)
+    } else if app.refstate_modal_visible {
+        match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Esc => {
+                    app.refstate_modal_visible = false;
+                },
+                KeyCode::Char('1') => {
+                    app.input_param = 1;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('2') => {
+                    app.input_param = 2;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('c') => {
+                    app.ref_state = app.ref_state.next();
+                    recalculate(app);
+                },
+                _ =>{},
             },
+            _ => {}
+        }
+        Ok(false)
+    } else if app.fugacity_modal_visible {
+        match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Esc => {
+                    app.fugacity_modal_visible = false;
+                    app.fugacity_scroll = 0;
+                },
+                KeyCode::Up => {
+                    app.fugacity_scroll = app.fugacity_scroll.saturating_sub(1);
+                },
+                KeyCode::Down => {
+                    app.fugacity_scroll += 1;
+                },
+                _ =>{},
             },
             _ => {}
         }
         Ok(false)
-    } else if app.gas_modal_visible {
+    } else if app.sweep_modal_visible {
         match event::read()? {
             Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
-                KeyCode::Enter => {
-                    app.gas_modal_visible = false;
+                KeyCode::Esc => {
+                    app.sweep_modal_visible = false;
+                    app.sweep_scroll = 0;
+                    app.sweep_message.clear();
+                },
+                KeyCode::Up => {
+                    app.sweep_scroll = app.sweep_scroll.saturating_sub(1);
+                },
+                KeyCode::Down => {
+                    app.sweep_scroll += 1;
+                },
+                KeyCode::Left => {
+                    app.sweep_cursor = (app.sweep_cursor + sweep::PROPERTIES.len() - 1) % sweep::PROPERTIES.len();
+                },
+                KeyCode::Right => {
+                    app.sweep_cursor = (app.sweep_cursor + 1) % sweep::PROPERTIES.len();
+                },
+                KeyCode::Char('1') => {
+                    app.input_param = 1;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('2') => {
+                    app.input_param = 2;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('3') => {
+                    app.input_param = 3;
+                    app.input_modal_active = true;
+                },
+                KeyCode::Char('v') => {
+                    let variable = app.sweep_variable.next(&app.gas_comp);
+                    sweep::set_variable(app, variable);
+                },
+                KeyCode::Char('c') => {
+                    sweep::next_component(app);
+                },
+                KeyCode::Char(' ') => {
+                    sweep::toggle_property(app);
                 },
+                KeyCode::Char('x') => {
+                    sweep::export_csv(app);
+                },
+                _ =>{},
+            },
+            _ => {}
+        }
+        Ok(false)
+    } else if app.grid_modal_visible {
+        match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
                 KeyCode::Esc => {
-                    app.gas_modal_visible = false;
+                    app.grid_modal_visible = false;
+                    app.grid_message.clear();
                 },
                 KeyCode::Char('1') => {
-                    set_gas(app, get_gas_comp(gas::Gas::Air));
-                    app.gas_text = "Air";
-                    app.gas_modal_visible = false;
+                    app.input_param = 1;
+                    app.input_modal_active = true;
                 },
                 KeyCode::Char('2') => {
-                    set_gas(app, get_gas_comp(gas::Gas::Argon));
-                    app.gas_text = "Argon";
-                    app.gas_modal_visible = false;
+                    app.input_param = 2;
+                    app.input_modal_active = true;
                 },
                 KeyCode::Char('3') => {
-                    set_gas(app, get_gas_comp(gas::Gas::CO));
-                    app.gas_text = "Carbon Monoxide";
-                    app.gas_modal_visible = false;
+                    app.input_param = 3;
+                    app.input_modal_active = true;
                 },
                 KeyCode::Char('4') => {
-                    set_gas(app, get_gas_comp(gas::Gas::CO2));
-                    app.gas_text = "Carbon Dioxide";
-                    app.gas_modal_visible = false;
+                    app.input_param = 4;
+                    app.input_modal_active = true;
                 },
                 KeyCode::Char('5') => {
-                    set_gas(app, get_gas_comp(gas::Gas::Helium));
-                    app.gas_text = "Helium";
-                    app.gas_modal_visible = false;
+                    app.input_param = 5;
+                    app.input_modal_active = true;
                 },
                 KeyCode::Char('6') => {
-                    set_gas(app, get_gas_comp(gas::Gas::Hydrogen));
-                    app.gas_text = "Hydrogen";
-                    app.gas_modal_visible = false;
-                },
-                KeyCode::Char('7') => {
-                    set_gas(app, get_gas_comp(gas::Gas::Nitrogen));
-                    app.gas_text = "Nitrogen";
-                    app.gas_modal_visible = false;
+                    app.input_param = 6;
+                    app.input_modal_active = true;
                 },
-                KeyCode::Char('8') => {
-                    set_gas(app, get_gas_comp(gas::Gas::Oxygen));
-                    app.gas_text = "Oxygen";
-                    app.gas_modal_visible = false;
+                KeyCode::Char('f') => {
+                    app.grid_format = app.grid_format.next();
+                    app.grid_message.clear();
                 },
-                KeyCode::Char('9') => {
-                    app.gas_text = "Custom";
-                    app.gas_modal_visible = false;
+                KeyCode::Char('x') => {
+                    grid::export(app);
                 },
                 _ =>{},
             },
@@ -525,6 +1595,18 @@ fn handle_events(app: &mut App) -> std::io::Result<bool> {
                     app.select_unit_modal_visible = false;
                     app.flow_units_modal_visible = true;
                 },
+                KeyCode::Char('8') => {
+                    app.select_unit_modal_visible = false;
+                    app.power_units_modal_visible = true;
+                },
+                KeyCode::Char('9') => {
+                    app.select_unit_modal_visible = false;
+                    app.viscosity_units_modal_visible = true;
+                },
+                KeyCode::Char('0') => {
+                    app.select_unit_modal_visible = false;
+                    app.conductivity_units_modal_visible = true;
+                },
                 _ =>{},
             },
             _ => {}
@@ -736,6 +1818,80 @@ fn handle_events(app: &mut App) -> std::io::Result<bool> {
             _ => {}
         }
         Ok(false)
+    } else if app.power_units_modal_visible {
+        match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Enter => {
+                    app.power_units_modal_visible = false;
+                },
+                KeyCode::Esc => {
+                    app.power_units_modal_visible = false;
+                },
+                KeyCode::Char('1') => {
+                    app.units.power = units::Power::kW;
+                    app.power_units_modal_visible = false;
+                },
+                KeyCode::Char('2') => {
+                    app.units.power = units::Power::hp;
+                    app.power_units_modal_visible = false;
+                },
+                KeyCode::Char('3') => {
+                    app.units.power = units::Power::BTU_h;
+                    app.power_units_modal_visible = false;
+                },
+                _ =>{},
+            },
+            _ => {}
+        }
+        Ok(false)
+    } else if app.viscosity_units_modal_visible {
+        match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Enter => {
+                    app.viscosity_units_modal_visible = false;
+                },
+                KeyCode::Esc => {
+                    app.viscosity_units_modal_visible = false;
+                },
+                KeyCode::Char('1') => {
+                    app.units.viscosity = units::Viscosity::cP;
+                    app.viscosity_units_modal_visible = false;
+                },
+                KeyCode::Char('2') => {
+                    app.units.viscosity = units::Viscosity::Pa_s;
+                    app.viscosity_units_modal_visible = false;
+                },
+                KeyCode::Char('3') => {
+                    app.units.viscosity = units::Viscosity::lbm_ft_s;
+                    app.viscosity_units_modal_visible = false;
+                },
+                _ =>{},
+            },
+            _ => {}
+        }
+        Ok(false)
+    } else if app.conductivity_units_modal_visible {
+        match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Enter => {
+                    app.conductivity_units_modal_visible = false;
+                },
+                KeyCode::Esc => {
+                    app.conductivity_units_modal_visible = false;
+                },
+                KeyCode::Char('1') => {
+                    app.units.conductivity = units::Conductivity::W_m_K;
+                    app.conductivity_units_modal_visible = false;
+                },
+                KeyCode::Char('2') => {
+                    app.units.conductivity = units::Conductivity::BTU_hr_ft_F;
+                    app.conductivity_units_modal_visible = false;
+                },
+                _ =>{},
+            },
+            _ => {}
+        }
+        Ok(false)
     } else {
         match event::read()? {
             Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
@@ -746,7 +1902,7 @@ fn handle_events(app: &mut App) -> std::io::Result<bool> {
                 KeyCode::Char('i') => set_inlet_conditions(app),
                 KeyCode::Char('o') => set_outlet_conditions(app),
                 KeyCode::Char('m') => {
-                    app.use_gerg2008 = ! app.use_gerg2008;
+                    app.model = app.model.next();
                     recalculate(app);
                 },
                 KeyCode::Char('c') => {
@@ -771,6 +1927,12 @@ fn handle_events(app: &mut App) -> std::io::Result<bool> {
                     app.wheel_diameter_modal_visible = true;
                     app.input_modal_active = true;
                 }
+                KeyCode::Char('h') => {
+                    app.hydrate_modal_visible = ! app.hydrate_modal_visible
+                }
+                KeyCode::Char('a') => {
+                    app.tools_modal_visible = ! app.tools_modal_visible
+                }
                 _ => {}
             },
             _ => {}
@@ -780,32 +1942,22 @@ fn handle_events(app: &mut App) -> std::io::Result<bool> {
 }
 
 fn get_pressure(app: &mut App) -> f64 {
-    if app.use_gerg2008 {
-        units::get_pressure(app.gerg_cur_state.p, app.units.pressure)
-    } else {
-        units::get_pressure(app.aga8_cur_state.p, app.units.pressure)
-    }
+    units::get_pressure(app.cur_state.p, app.units.pressure)
 }
 
 fn set_cur_pressure(pressure: f64, app: &mut App) {
-    let p_val = units::set_pressure(pressure, app.units.pressure);
-    app.aga8_cur_state.p = p_val;
-    app.gerg_cur_state.p = p_val;
+    app.cur_state.p = units::set_pressure(pressure, app.units.pressure);
     recalculate(app);
 }
 
 fn set_pressure(app: &mut App, state: GasState) {
-    let p_aga8 = units::get_pressure(app.aga8_cur_state.p, app.units.pressure);
-    let p_gerg = units::get_pressure(app.gerg_cur_state.p, app.units.pressure);
     match state {
         GasState::Inlet => {
-            app.aga8_inlet_state.p = units::set_pressure(p_aga8, app.units.pressure);
-            app.gerg_inlet_state.p = units::set_pressure(p_gerg, app.units.pressure);
+            app.inlet_state.p = app.cur_state.p;
             recalculate(app);
         }
         GasState::Outlet => {
-            app.aga8_outlet_state.p = units::set_pressure(p_aga8, app.units.pressure);
-            app.gerg_outlet_state.p = units::set_pressure(p_gerg, app.units.pressure);
+            app.outlet_state.p = app.cur_state.p;
             recalculate(app);
         }
         _ => {}
@@ -813,17 +1965,11 @@ fn set_pressure(app: &mut App, state: GasState) {
 }
 
 fn get_temperature(app: &mut App) -> f64 {
-    if app.use_gerg2008 {
-        units::get_temperature(app.gerg_cur_state.t, app.units.temp)
-    } else {
-        units::get_temperature(app.aga8_cur_state.t, app.units.temp)
-    }
+    units::get_temperature(app.cur_state.t, app.units.temp)
 }
 
 fn set_cur_temperature(temperature: f64, app: &mut App) {
-    let t_val = units::set_temperature(temperature, app.units.temp);
-    app.aga8_cur_state.t = t_val;
-    app.gerg_cur_state.t = t_val;
+    app.cur_state.t = units::set_temperature(temperature, app.units.temp);
     recalculate(app);
 }
 
@@ -833,23 +1979,19 @@ fn set_cur_flow(flow_rate: f64, app: &mut App) {
         app.units.flow, 
         &app.gas_comp, 
         app.stp_60_F, 
-        app.use_gerg2008
+        app.model
     );
     app.flow_val = flow_val;
 }
 
 fn set_temperature(app: &mut App, state: GasState) {
-    let t_aga8 = units::get_temperature(app.aga8_cur_state.t, app.units.temp);
-    let t_gerg = units::get_temperature(app.gerg_cur_state.t, app.units.temp);
     match state {
         GasState::Inlet => {
-            app.aga8_inlet_state.t = units::set_temperature(t_aga8, app.units.temp);
-            app.gerg_inlet_state.t = units::set_temperature(t_gerg, app.units.temp);
+            app.inlet_state.t = app.cur_state.t;
             recalculate(app);
         }
         GasState::Outlet => {
-            app.aga8_outlet_state.t = units::set_temperature(t_aga8, app.units.temp);
-            app.gerg_outlet_state.t = units::set_temperature(t_gerg, app.units.temp);
+            app.outlet_state.t = app.cur_state.t;
             recalculate(app);
         }
         _ => {}
@@ -857,88 +1999,46 @@ fn set_temperature(app: &mut App, state: GasState) {
 }
 
 fn get_density(app: &mut App) -> f64 {
-    if app.use_gerg2008 {
-        app.gerg_cur_state.d
-    } else {
-        app.aga8_cur_state.d
-    }
+    app.cur_state.d
 }
 
 fn get_molar_mass(app: &mut App) -> f64 {
-    if app.use_gerg2008 {
-        app.gerg_cur_state.mm
-    } else {
-        app.aga8_cur_state.mm
-    }
+    app.cur_state.mm
 }
 
 fn get_internal_energy(app: &mut App) -> f64 {
-    if app.use_gerg2008 {
-        units::get_energy(app.gerg_cur_state.u, app.units.energy, app.gerg_cur_state.mm)
-    } else {
-        units::get_energy(app.aga8_cur_state.u, app.units.energy, app.aga8_cur_state.mm)
-    }
+    units::get_energy(app.cur_state.u, app.units.energy, app.cur_state.mm)
 }
 
 fn get_enthalpy(app: &mut App) -> f64 {
-    if app.use_gerg2008 {
-        units::get_energy(app.gerg_cur_state.h, app.units.energy, app.gerg_cur_state.mm)
-    } else {
-        units::get_energy(app.aga8_cur_state.h, app.units.energy, app.aga8_cur_state.mm)
-    }
+    units::get_energy(app.cur_state.h, app.units.energy, app.cur_state.mm)
 }
 
 fn get_entropy(app: &mut App) -> f64 {
-    if app.use_gerg2008 {
-        units::get_entropy(app.gerg_cur_state.s, app.units.entropy, app.gerg_cur_state.mm)
-    } else {
-        units::get_entropy(app.aga8_cur_state.s, app.units.entropy, app.aga8_cur_state.mm)
-    }
+    units::get_entropy(app.cur_state.s, app.units.entropy, app.cur_state.mm)
 }
 
 fn get_cp(app: &mut App) -> f64 {
-    if app.use_gerg2008 {
-        units::get_entropy(app.gerg_cur_state.cp, app.units.entropy, app.gerg_cur_state.mm)
-    } else {
-        units::get_entropy(app.aga8_cur_state.cp, app.units.entropy, app.aga8_cur_state.mm)
-    }
+    units::get_entropy(app.cur_state.cp, app.units.entropy, app.cur_state.mm)
 }
 
 fn get_cv(app: &mut App) -> f64 {
-    if app.use_gerg2008 {
-        units::get_entropy(app.gerg_cur_state.cv, app.units.entropy, app.gerg_cur_state.mm)
-    } else {
-        units::get_entropy(app.aga8_cur_state.cv, app.units.entropy, app.aga8_cur_state.mm)
-    }
+    units::get_entropy(app.cur_state.cv, app.units.entropy, app.cur_state.mm)
 }
 
 fn get_speed(app: &mut App) -> f64 {
-    if app.use_gerg2008 {
-        units::get_speed(app.gerg_cur_state.w, app.units.speed)
-    } else {
-        units::get_speed(app.aga8_cur_state.w, app.units.speed)
-    }
+    units::get_speed(app.cur_state.w, app.units.speed)
 }
 
 fn get_gibbs_energy(app: &mut App) -> f64 {
-    if app.use_gerg2008 {
-        units::get_gibbs_energy(app.gerg_cur_state.g, app.units.pressure, app.units.temp)
-    } else {
-        units::get_gibbs_energy(app.aga8_cur_state.g, app.units.pressure, app.units.temp)
-    }
+    units::get_gibbs_energy(app.cur_state.g, app.units.pressure, app.units.temp)
 }
 
 fn get_jt_coeff(app: &mut App) -> f64 {
-    if app.use_gerg2008 {
-        units::get_jt_coeff(app.gerg_cur_state.jt, app.units.jt_coeff)
-    } else {
-        units::get_jt_coeff(app.aga8_cur_state.jt, app.units.jt_coeff)
-    }
+    units::get_jt_coeff(app.cur_state.jt, app.units.jt_coeff)
 }
 
 fn set_inlet_conditions(app: &mut App) {
-    app.aga8_inlet_state.set_composition(&copy_composition(&app.gas_comp));
-    app.gerg_inlet_state.set_composition(&copy_composition(&app.gas_comp));
     set_pressure(app, GasState::Inlet);
     set_temperature(app, GasState::Inlet);
     recalculate(app);
@@ -946,8 +2046,6 @@ fn set_inlet_conditions(app: &mut App) {
 }
 
 fn set_outlet_conditions(app: &mut App) {
-    app.aga8_outlet_state.set_composition(&copy_composition(&app.gas_comp));
-    app.gerg_outlet_state.set_composition(&copy_composition(&app.gas_comp));
     set_pressure(app, GasState::Outlet);
     set_temperature(app, GasState::Outlet);
     recalculate(app);
@@ -1008,62 +2106,61 @@ fn get_gas_properties(app: &'_ App, state: GasState) -> Vec<ListItem<'_>> {
         let mut g;
         let mut jt;
         let jt_str = app.units.jt_coeff.print_unit();
+        let kt;
+        let kt_str = format!("1/{}", p_str);
+        let beta;
+        let beta_str = format!("1/{}", t_str);
+        let dp_dt;
+        let dp_dt_str = format!("{}/{}", p_str, t_str);
+        let dp_dd;
+        let dp_dd_str = format!("{}/({})", p_str, d_str);
+        let mut b;
+        let b_str = app.units.density.print_volume_unit();
+        let tp;
+        let visc_str = app.units.viscosity.print_unit();
+        let kin_visc_str = app.units.viscosity.print_kinematic_unit();
+        let cond_str = app.units.conductivity.print_unit();
 
         match state {
             GasState::Current => {
-                if app.use_gerg2008 {
-                    p = app.gerg_cur_state.p;
-                    p = units::get_pressure(p, app.units.pressure);
-                    t = app.gerg_cur_state.t;
-                    t = units::get_temperature(t, app.units.temp);
-                    mm = app.gerg_cur_state.mm;
-                    d = app.gerg_cur_state.d;
-                    d = units::get_density(d, app.units.density, mm);
-                    u = app.gerg_cur_state.u;
-                    u = units::get_energy(u, app.units.energy, mm);
-                    h = app.gerg_cur_state.h;
-                    h = units::get_energy(h, app.units.energy, mm);
-                    s = app.gerg_cur_state.s;
-                    s = units::get_entropy(s, app.units.entropy, mm);
-                    cp = app.gerg_cur_state.cp;
-                    cp = units::get_entropy(cp, app.units.entropy, mm);
-                    cv = app.gerg_cur_state.cv;
-                    cv = units::get_entropy(cv, app.units.entropy, mm);
-                    k = app.gerg_cur_state.kappa;
-                    z = app.gerg_cur_state.z;
-                    w = app.gerg_cur_state.w;
-                    w = units::get_speed(w, app.units.speed);
-                    g = app.gerg_cur_state.g;
-                    g = units::get_energy(g, app.units.energy, mm);
-                    jt = app.gerg_cur_state.jt;
-                    jt = units::get_jt_coeff(jt, app.units.jt_coeff);
-                } else {
-                    p = app.aga8_cur_state.p;
-                    p = units::get_pressure(p, app.units.pressure);
-                    t = app.aga8_cur_state.t;
-                    t = units::get_temperature(t, app.units.temp);
-                    mm = app.aga8_cur_state.mm;
-                    d = app.aga8_cur_state.d;
-                    d = units::get_density(d, app.units.density, mm);
-                    u = app.aga8_cur_state.u;
-                    u = units::get_energy(u, app.units.energy, mm);
-                    h = app.aga8_cur_state.h;
-                    h = units::get_energy(h, app.units.energy, mm);
-                    s = app.aga8_cur_state.s;
-                    s = units::get_entropy(s, app.units.entropy, mm);
-                    cp = app.aga8_cur_state.cp;
-                    cp = units::get_entropy(cp, app.units.entropy, mm);
-                    cv = app.aga8_cur_state.cv;
-                    cv = units::get_entropy(cv, app.units.entropy, mm);
-                    k = app.aga8_cur_state.kappa;
-                    z = app.aga8_cur_state.z;
-                    w = app.aga8_cur_state.w;
-                    w = units::get_speed(w, app.units.speed);
-                    g = app.aga8_cur_state.g;
-                    g = units::get_energy(g, app.units.energy, mm);
-                    jt = app.aga8_cur_state.jt;
-                    jt = units::get_jt_coeff(jt, app.units.jt_coeff);
-            }
+                let props = app.ref_offset.apply(&app.cur_state);
+                p = props.p;
+                p = units::get_pressure(p, app.units.pressure);
+                t = props.t;
+                t = units::get_temperature(t, app.units.temp);
+                mm = props.mm;
+                d = props.d;
+                d = units::get_density(d, app.units.density, mm);
+                u = props.u;
+                u = units::get_energy(u, app.units.energy, mm);
+                h = props.h;
+                h = units::get_energy(h, app.units.energy, mm);
+                s = props.s;
+                s = units::get_entropy(s, app.units.entropy, mm);
+                cp = props.cp;
+                cp = units::get_entropy(cp, app.units.entropy, mm);
+                cv = props.cv;
+                cv = units::get_entropy(cv, app.units.entropy, mm);
+                k = props.kappa;
+                z = props.z;
+                w = props.w;
+                w = units::get_speed(w, app.units.speed);
+                g = props.g;
+                g = units::get_energy(g, app.units.energy, mm);
+                jt = props.jt;
+                jt = units::get_jt_coeff(jt, app.units.jt_coeff);
+                kt = units::get_compressibility(props.isothermal_compressibility(), app.units.pressure);
+                beta = units::get_expansivity(props.volume_expansivity(), app.units.temp);
+                dp_dt = units::get_dp_dt(props.dp_dt, app.units.pressure, app.units.temp);
+                dp_dd = units::get_dp_dd(props.dp_dd, app.units.pressure, app.units.density, mm);
+                b = eos::second_virial(&app.gas_comp, app.model, props.t);
+                b = units::get_specific_volume(b, app.units.density, mm);
+                tp = transport::transport_properties(&app.gas_comp, app.model, props.t, props.d, props.mm, props.cp);
+            let p_kpa = units::set_pressure(p, app.units.pressure);
+            let t_hyd = match hydrate::hydrate_temp(app, p_kpa) {
+                Some(t_hyd) => format!("{:.4} {}", units::get_temperature(t_hyd, app.units.temp), t_str),
+                None => "n/a (gas gravity method)".to_string(),
+            };
             let items = vec![
                 ListItem::new(format!("{:<18}", app.gas_text)).fg(Color::White).bg(Color::Blue),
                 ListItem::new(format!("{:<18} {:.4} {}", "Pressure:", p, p_str)).fg(Color::White).bg(Color::Black),
@@ -1080,63 +2177,53 @@ fn get_gas_properties(app: &'_ App, state: GasState) -> Vec<ListItem<'_>> {
                 ListItem::new(format!("{:<18} {:.4} {}", "Speed of Sound:", w, speed_str)).fg(Color::Black).bg(Color::DarkGray),
                 // ListItem::new(format!("{:<18} {:.4} {}", "Gibbs Energy:", g, energy_str)).fg(Color::White).bg(Color::Black),
                 ListItem::new(format!("{:<18} {:.4} {}", "JT Coeff:", jt, jt_str)).fg(Color::White).bg(Color::Black),
+                ListItem::new(format!("{:<18} {:.6} {}", "Isothermal Comp:", kt, kt_str)).fg(Color::Black).bg(Color::DarkGray),
+                ListItem::new(format!("{:<18} {:.6} {}", "Vol Expansivity:", beta, beta_str)).fg(Color::White).bg(Color::Black),
+                ListItem::new(format!("{:<18} {:.4} {}", "dP/dT (const D):", dp_dt, dp_dt_str)).fg(Color::Black).bg(Color::DarkGray),
+                ListItem::new(format!("{:<18} {:.4} {}", "dP/dD (const T):", dp_dd, dp_dd_str)).fg(Color::White).bg(Color::Black),
+                ListItem::new(format!("{:<18} {:.6} {}", "2nd Virial Coeff:", b, b_str)).fg(Color::Black).bg(Color::DarkGray),
+                ListItem::new(format!("{:<18} {:.6} {}", "Viscosity:", units::get_viscosity(tp.viscosity, app.units.viscosity), visc_str)).fg(Color::White).bg(Color::Black),
+                ListItem::new(format!("{:<18} {:.6} {}", "Kin. Viscosity:", units::get_kinematic_viscosity(tp.kinematic_viscosity, app.units.viscosity), kin_visc_str)).fg(Color::Black).bg(Color::DarkGray),
+                ListItem::new(format!("{:<18} {:.6} {}", "Conductivity:", units::get_conductivity(tp.conductivity, app.units.conductivity), cond_str)).fg(Color::White).bg(Color::Black),
+                ListItem::new(format!("{:<18} {:.4} {}", "Prandtl No:", tp.prandtl, "[]")).fg(Color::Black).bg(Color::DarkGray),
+                ListItem::new(format!("{:<18} {}", "Hydrate Temp:", t_hyd)).fg(Color::White).bg(Color::Black),
             ];
                 return items
             },
             GasState::Inlet => {
-                if app.use_gerg2008 {
-                    p = app.gerg_inlet_state.p;
-                    p = units::get_pressure(p, app.units.pressure);
-                    t = app.gerg_inlet_state.t;
-                    t = units::get_temperature(t, app.units.temp);
-                    mm = app.gerg_inlet_state.mm;
-                    d = app.gerg_inlet_state.d;
-                    d = units::get_density(d, app.units.density, mm);
-                    u = app.gerg_inlet_state.u;
-                    u = units::get_energy(u, app.units.energy, mm);
-                    h = app.gerg_inlet_state.h;
-                    h = units::get_energy(h, app.units.energy, mm);
-                    s = app.gerg_inlet_state.s;
-                    s = units::get_entropy(s, app.units.entropy, mm);
-                    cp = app.gerg_inlet_state.cp;
-                    cp = units::get_entropy(cp, app.units.entropy, mm);
-                    cv = app.gerg_inlet_state.cv;
-                    cv = units::get_entropy(cv, app.units.entropy, mm);
-                    k = app.gerg_inlet_state.kappa;
-                    z = app.gerg_inlet_state.z;
-                    w = app.gerg_inlet_state.w;
-                    w = units::get_speed(w, app.units.speed);
-                    g = app.gerg_inlet_state.g;
-                    g = units::get_energy(g, app.units.energy, mm);
-                    jt = app.gerg_inlet_state.jt;
-                    jt = units::get_jt_coeff(jt, app.units.jt_coeff);
-                } else {
-                    p = app.aga8_inlet_state.p;
-                    p = units::get_pressure(p, app.units.pressure);
-                    t = app.aga8_inlet_state.t;
-                    t = units::get_temperature(t, app.units.temp);
-                    mm = app.aga8_inlet_state.mm;
-                    d = app.aga8_inlet_state.d;
-                    d = units::get_density(d, app.units.density, mm);
-                    u = app.aga8_inlet_state.u;
-                    u = units::get_energy(u, app.units.energy, mm);
-                    h = app.aga8_inlet_state.h;
-                    h = units::get_energy(h, app.units.energy, mm);
-                    s = app.aga8_inlet_state.s;
-                    s = units::get_entropy(s, app.units.entropy, mm);
-                    cp = app.aga8_inlet_state.cp;
-                    cp = units::get_entropy(cp, app.units.entropy, mm);
-                    cv = app.aga8_inlet_state.cv;
-                    cv = units::get_entropy(cv, app.units.entropy, mm);
-                    k = app.aga8_inlet_state.kappa;
-                    z = app.aga8_inlet_state.z;
-                    w = app.aga8_inlet_state.w;
-                    w = units::get_speed(w, app.units.speed);
-                    g = app.aga8_inlet_state.g;
-                    g = units::get_energy(g, app.units.energy, mm);
-                    jt = app.aga8_inlet_state.jt;
-                    jt = units::get_jt_coeff(jt, app.units.jt_coeff);
-            }
+                let props = app.ref_offset.apply(&app.inlet_state);
+                p = props.p;
+                p = units::get_pressure(p, app.units.pressure);
+                t = props.t;
+                t = units::get_temperature(t, app.units.temp);
+                mm = props.mm;
+                d = props.d;
+                d = units::get_density(d, app.units.density, mm);
+                u = props.u;
+                u = units::get_energy(u, app.units.energy, mm);
+                h = props.h;
+                h = units::get_energy(h, app.units.energy, mm);
+                s = props.s;
+                s = units::get_entropy(s, app.units.entropy, mm);
+                cp = props.cp;
+                cp = units::get_entropy(cp, app.units.entropy, mm);
+                cv = props.cv;
+                cv = units::get_entropy(cv, app.units.entropy, mm);
+                k = props.kappa;
+                z = props.z;
+                w = props.w;
+                w = units::get_speed(w, app.units.speed);
+                g = props.g;
+                g = units::get_energy(g, app.units.energy, mm);
+                jt = props.jt;
+                jt = units::get_jt_coeff(jt, app.units.jt_coeff);
+                kt = units::get_compressibility(props.isothermal_compressibility(), app.units.pressure);
+                beta = units::get_expansivity(props.volume_expansivity(), app.units.temp);
+                dp_dt = units::get_dp_dt(props.dp_dt, app.units.pressure, app.units.temp);
+                dp_dd = units::get_dp_dd(props.dp_dd, app.units.pressure, app.units.density, mm);
+                b = eos::second_virial(&app.gas_comp, app.model, props.t);
+                b = units::get_specific_volume(b, app.units.density, mm);
+                tp = transport::transport_properties(&app.gas_comp, app.model, props.t, props.d, props.mm, props.cp);
             let items = vec![
                 ListItem::new(format!("{:<18}", app.gas_text)).fg(Color::White).bg(Color::Blue),
                 ListItem::new(format!("{:<18} {:.4} {}", "Pressure:", p, p_str)).fg(Color::Green).bg(Color::Black),
@@ -1153,64 +2240,53 @@ fn get_gas_properties(app: &'_ App, state: GasState) -> Vec<ListItem<'_>> {
                 ListItem::new(format!("{:<18} {:.4} {}", "Speed of Sound:", w, speed_str)).fg(Color::Black).bg(Color::DarkGray),
                 // ListItem::new(format!("{:<18} {:.4} {}", "Gibbs Energy:", g, energy_str)).fg(Color::Green).bg(Color::Black),
                 ListItem::new(format!("{:<18} {:.4} {}", "JT Coeff:", jt, jt_str)).fg(Color::Green).bg(Color::Black),
+                ListItem::new(format!("{:<18} {:.6} {}", "Isothermal Comp:", kt, kt_str)).fg(Color::Black).bg(Color::DarkGray),
+                ListItem::new(format!("{:<18} {:.6} {}", "Vol Expansivity:", beta, beta_str)).fg(Color::Green).bg(Color::Black),
+                ListItem::new(format!("{:<18} {:.4} {}", "dP/dT (const D):", dp_dt, dp_dt_str)).fg(Color::Black).bg(Color::DarkGray),
+                ListItem::new(format!("{:<18} {:.4} {}", "dP/dD (const T):", dp_dd, dp_dd_str)).fg(Color::Green).bg(Color::Black),
+                ListItem::new(format!("{:<18} {:.6} {}", "2nd Virial Coeff:", b, b_str)).fg(Color::Black).bg(Color::DarkGray),
+                ListItem::new(format!("{:<18} {:.6} {}", "Viscosity:", units::get_viscosity(tp.viscosity, app.units.viscosity), visc_str)).fg(Color::Green).bg(Color::Black),
+                ListItem::new(format!("{:<18} {:.6} {}", "Kin. Viscosity:", units::get_kinematic_viscosity(tp.kinematic_viscosity, app.units.viscosity), kin_visc_str)).fg(Color::Black).bg(Color::DarkGray),
+                ListItem::new(format!("{:<18} {:.6} {}", "Conductivity:", units::get_conductivity(tp.conductivity, app.units.conductivity), cond_str)).fg(Color::Green).bg(Color::Black),
+                ListItem::new(format!("{:<18} {:.4} {}", "Prandtl No:", tp.prandtl, "[]")).fg(Color::Black).bg(Color::DarkGray),
             ];
                 return items
             },
             GasState::Outlet => {
-                if app.use_gerg2008 {
-                    p = app.gerg_outlet_state.p;
-                    p = units::get_pressure(p, app.units.pressure);
-                    t = app.gerg_outlet_state.t;
-                    t = units::get_temperature(t, app.units.temp);
-                    mm = app.gerg_outlet_state.mm;
-                    d = app.gerg_outlet_state.d;
-                    d = units::get_density(d, app.units.density, mm);
-                    u = app.gerg_outlet_state.u;
-                    u = units::get_energy(u, app.units.energy, mm);
-                    h = app.gerg_outlet_state.h;
-                    h = units::get_energy(h, app.units.energy, mm);
-                    s = app.gerg_outlet_state.s;
-                    s = units::get_entropy(s, app.units.entropy, mm);
-                    cp = app.gerg_outlet_state.cp;
-                    cp = units::get_entropy(cp, app.units.entropy, mm);
-                    cv = app.gerg_outlet_state.cv;
-                    cv = units::get_entropy(cv, app.units.entropy, mm);
-                    k = app.gerg_outlet_state.kappa;
-                    z = app.gerg_outlet_state.z;
-                    w = app.gerg_outlet_state.w;
-                    w = units::get_speed(w, app.units.speed);
-                    g = app.gerg_outlet_state.g;
-                    g = units::get_energy(g, app.units.energy, mm);
-                    jt = app.gerg_outlet_state.jt;
-                    jt = units::get_jt_coeff(jt, app.units.jt_coeff);
-                } else {
-                    p = app.aga8_outlet_state.p;
-                    p = units::get_pressure(p, app.units.pressure);
-                    t = app.aga8_outlet_state.t;
-                    t = units::get_temperature(t, app.units.temp);
-                    mm = app.aga8_outlet_state.mm;
-                    d = app.aga8_outlet_state.d;
-                    d = units::get_density(d, app.units.density, mm);
-                    u = app.aga8_outlet_state.u;
-                    u = units::get_energy(u, app.units.energy, mm);
-                    h = app.aga8_outlet_state.h;
-                    h = units::get_energy(h, app.units.energy, mm);
-                    s = app.aga8_outlet_state.s;
-                    s = units::get_entropy(s, app.units.entropy, mm);
-                    cp = app.aga8_outlet_state.cp;
-                    cp = units::get_entropy(cp, app.units.entropy, mm);
-                    cv = app.aga8_outlet_state.cv;
-                    cv = units::get_entropy(cv, app.units.entropy, mm);
-                    k = app.aga8_outlet_state.kappa;
-                    z = app.aga8_outlet_state.z;
-                    w = app.aga8_outlet_state.w;
-                    w = units::get_speed(w, app.units.speed);
-                    g = app.aga8_outlet_state.g;
-                    g = units::get_energy(g, app.units.energy, mm);
-                    jt = app.aga8_outlet_state.jt;
-                    jt = units::get_jt_coeff(jt, app.units.jt_coeff);
-            }
-            let items = vec![
+                let props = app.ref_offset.apply(&app.outlet_state);
+                p = props.p;
+                p = units::get_pressure(p, app.units.pressure);
+                t = props.t;
+                t = units::get_temperature(t, app.units.temp);
+                mm = props.mm;
+                d = props.d;
+                d = units::get_density(d, app.units.density, mm);
+                u = props.u;
+                u = units::get_energy(u, app.units.energy, mm);
+                h = props.h;
+                h = units::get_energy(h, app.units.energy, mm);
+                s = props.s;
+                s = units::get_entropy(s, app.units.entropy, mm);
+                cp = props.cp;
+                cp = units::get_entropy(cp, app.units.entropy, mm);
+                cv = props.cv;
+                cv = units::get_entropy(cv, app.units.entropy, mm);
+                k = props.kappa;
+                z = props.z;
+                w = props.w;
+                w = units::get_speed(w, app.units.speed);
+                g = props.g;
+                g = units::get_energy(g, app.units.energy, mm);
+                jt = props.jt;
+                jt = units::get_jt_coeff(jt, app.units.jt_coeff);
+                kt = units::get_compressibility(props.isothermal_compressibility(), app.units.pressure);
+                beta = units::get_expansivity(props.volume_expansivity(), app.units.temp);
+                dp_dt = units::get_dp_dt(props.dp_dt, app.units.pressure, app.units.temp);
+                dp_dd = units::get_dp_dd(props.dp_dd, app.units.pressure, app.units.density, mm);
+                b = eos::second_virial(&app.gas_comp, app.model, props.t);
+                b = units::get_specific_volume(b, app.units.density, mm);
+                tp = transport::transport_properties(&app.gas_comp, app.model, props.t, props.d, props.mm, props.cp);
+            let mut items = vec![
                 ListItem::new(format!("{:<18}", app.gas_text)).fg(Color::White).bg(Color::Blue),
                 ListItem::new(format!("{:<18} {:.4} {}", "Pressure:", p, p_str)).fg(Color::Green).bg(Color::Black),
                 ListItem::new(format!("{:<18} {:.4} {}", "Temperature:", t, t_str)).fg(Color::Black).bg(Color::DarkGray),
@@ -1226,7 +2302,26 @@ fn get_gas_properties(app: &'_ App, state: GasState) -> Vec<ListItem<'_>> {
                 ListItem::new(format!("{:<18} {:.4} {}", "Speed of Sound:", w, speed_str)).fg(Color::Black).bg(Color::DarkGray),
                 // ListItem::new(format!("{:<18} {:.4} {}", "Gibbs Energy:", g, energy_str)).fg(Color::Green).bg(Color::Black),
                 ListItem::new(format!("{:<18} {:.4} {}", "JT Coeff:", jt, jt_str)).fg(Color::Green).bg(Color::Black),
+                ListItem::new(format!("{:<18} {:.6} {}", "Isothermal Comp:", kt, kt_str)).fg(Color::Black).bg(Color::DarkGray),
+                ListItem::new(format!("{:<18} {:.6} {}", "Vol Expansivity:", beta, beta_str)).fg(Color::Green).bg(Color::Black),
+                ListItem::new(format!("{:<18} {:.4} {}", "dP/dT (const D):", dp_dt, dp_dt_str)).fg(Color::Black).bg(Color::DarkGray),
+                ListItem::new(format!("{:<18} {:.4} {}", "dP/dD (const T):", dp_dd, dp_dd_str)).fg(Color::Green).bg(Color::Black),
+                ListItem::new(format!("{:<18} {:.6} {}", "2nd Virial Coeff:", b, b_str)).fg(Color::Black).bg(Color::DarkGray),
+                ListItem::new(format!("{:<18} {:.6} {}", "Viscosity:", units::get_viscosity(tp.viscosity, app.units.viscosity), visc_str)).fg(Color::Green).bg(Color::Black),
+                ListItem::new(format!("{:<18} {:.6} {}", "Kin. Viscosity:", units::get_kinematic_viscosity(tp.kinematic_viscosity, app.units.viscosity), kin_visc_str)).fg(Color::Black).bg(Color::DarkGray),
+                ListItem::new(format!("{:<18} {:.6} {}", "Conductivity:", units::get_conductivity(tp.conductivity, app.units.conductivity), cond_str)).fg(Color::Green).bg(Color::Black),
+                ListItem::new(format!("{:<18} {:.4} {}", "Prandtl No:", tp.prandtl, "[]")).fg(Color::Black).bg(Color::DarkGray),
             ];
+            if app.show_inlet_state && calculations::pressure_ratio(app) < 1.0 {
+                let item = match hydrate::hydrate_margin(app) {
+                    Some(margin) => {
+                        let margin_color = if margin < 0.0 { Color::Red } else { Color::Green };
+                        ListItem::new(format!("{:<18} {:.4} {}", "Hydrate Margin:", margin, t_str)).fg(margin_color).bg(Color::Black)
+                    },
+                    None => ListItem::new(format!("{:<18} {}", "Hydrate Margin:", "n/a (gas gravity method)")).fg(Color::White).bg(Color::Black),
+                };
+                items.push(item);
+            }
                 return items
             }
         }
@@ -1234,19 +2329,10 @@ fn get_gas_properties(app: &'_ App, state: GasState) -> Vec<ListItem<'_>> {
 }
 
 fn recalculate(app: &mut App) {
-    app.aga8_cur_state.density();
-    app.gerg_cur_state.density(0);
-    app.aga8_cur_state.properties();
-    app.gerg_cur_state.properties();
-
-    app.aga8_inlet_state.density();
-    app.gerg_inlet_state.density(0);
-    app.aga8_inlet_state.properties();
-    app.gerg_inlet_state.properties();
-
-    app.aga8_outlet_state.density();
-    app.gerg_outlet_state.density(0);
-    app.aga8_outlet_state.properties();
-    app.gerg_outlet_state.properties();
+    let mut eos = eos::new_eos(app.model, &app.gas_comp);
+    app.cur_state = eos.props_pt(app.cur_state.p, app.cur_state.t);
+    app.inlet_state = eos.props_pt(app.inlet_state.p, app.inlet_state.t);
+    app.outlet_state = eos.props_pt(app.outlet_state.p, app.outlet_state.t);
+    app.ref_offset = refstate::offset(app);
 }
 
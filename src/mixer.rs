@@ -0,0 +1,196 @@
+use aga8::composition::Composition;
+use ratatui::widgets::ListItem;
+use ratatui::
+    style::{
+        Color,
+        Stylize
+    };
+
+use crate::flash::{
+    self,
+    GasProps,
+};
+use crate::gas::{
+    components,
+    mix_composition,
+    set_gas,
+};
+use crate::units::{
+    self,
+    PrintUnit,
+};
+use crate::{
+    App,
+    copy_composition,
+    recalculate,
+};
+
+pub struct Stream {
+    pub name: &'static str,
+    pub comp: Composition,
+    pub p: f64,
+    pub t: f64,
+    pub flow: f64,
+}
+
+pub struct MixResult {
+    pub comp: Composition,
+    pub props: GasProps,
+    pub flow: f64,
+}
+
+// Adds the current state, composition and flow rate as a new feed stream
+pub fn add_stream(app: &mut App) {
+    let (p, t) = (app.cur_state.p, app.cur_state.t);
+    app.mixer_streams.push(Stream {
+        name: app.gas_text,
+        comp: copy_composition(&app.gas_comp),
+        p,
+        t,
+        flow: app.flow_val,
+    });
+}
+
+// Adiabatic mix: molar blend of compositions and an enthalpy balance at the
+// lowest feed pressure
+pub fn mix(app: &App) -> Option<MixResult> {
+    if app.mixer_streams.len() < 2 {
+        return None
+    }
+    let feeds: Vec<GasProps> = app.mixer_streams
+        .iter()
+        .map(|stream| flash::props_pt(&stream.comp, app.model, stream.p, stream.t))
+        .collect();
+
+    // Molar flows in kmol/s
+    let moles: Vec<f64> = app.mixer_streams
+        .iter()
+        .zip(feeds.iter())
+        .map(|(stream, props)| stream.flow / props.mm)
+        .collect();
+    let total_moles: f64 = moles.iter().sum();
+    if total_moles <= 0.0 {
+        return None
+    }
+
+    let weights: Vec<(&Composition, f64)> = app.mixer_streams
+        .iter()
+        .zip(moles.iter())
+        .map(|(stream, n)| (&stream.comp, *n))
+        .collect();
+    let comp = mix_composition(&weights);
+
+    let h_mix = feeds.iter().zip(moles.iter()).map(|(props, n)| props.h * n).sum::<f64>() / total_moles;
+    let t_guess = feeds.iter().zip(moles.iter()).map(|(props, n)| props.t * n).sum::<f64>() / total_moles;
+    let p_mix = app.mixer_streams.iter().map(|stream| stream.p).fold(f64::INFINITY, f64::min);
+    let props = flash::props_ph(&comp, app.model, p_mix, h_mix, t_guess);
+    let flow = app.mixer_streams.iter().map(|stream| stream.flow).sum();
+
+    Some(MixResult {
+        comp,
+        props,
+        flow,
+    })
+}
+
+// Loads the mixed stream as the current state
+pub fn load_mixture(app: &mut App) {
+    let result = match mix(app) {
+        Some(result) => result,
+        None => return,
+    };
+    set_gas(app, result.comp);
+    app.gas_text = "Mixture";
+    app.cur_state.p = result.props.p;
+    app.cur_state.t = result.props.t;
+    app.flow_val = result.flow;
+    recalculate(app);
+}
+
+pub fn run_mixer(app: &App) -> Vec<ListItem<'static>> {
+    let p_str = app.units.pressure.print_unit();
+    let t_str = app.units.temp.print_unit();
+    let flow_str = app.units.flow.print_unit();
+
+    let mut items = vec![];
+    for (i, stream) in app.mixer_streams.iter().enumerate() {
+        let flow = units::get_flow(stream.flow, app.units.flow, &stream.comp, app.stp_60_F, app.model);
+        items.push(
+            ListItem::new(
+                format!("S{:<2} {:<16} {:.4} {}  {:.4} {}  {:.4} {}",
+                    i + 1,
+                    stream.name,
+                    units::get_pressure(stream.p, app.units.pressure), p_str,
+                    units::get_temperature(stream.t, app.units.temp), t_str,
+                    flow, flow_str,
+                )
+            )
+                .fg(Color::LightYellow)
+                .bg(Color::Black)
+        );
+    }
+
+    let result = match mix(app) {
+        Some(result) => result,
+        None => {
+            items.push(
+                ListItem::new("Add at least two streams (A adds the current state and flow)")
+                    .fg(Color::Red)
+                    .bg(Color::Black)
+            );
+            return items
+        }
+    };
+
+    let flow = units::get_flow(result.flow, app.units.flow, &result.comp, app.stp_60_F, app.model);
+    items.push(
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Mixed Pressure:", units::get_pressure(result.props.p, app.units.pressure), p_str,
+            )
+        )
+            .fg(Color::LightCyan)
+            .bg(Color::Black)
+    );
+    items.push(
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Mixed Temperature:", units::get_temperature(result.props.t, app.units.temp), t_str,
+            )
+        )
+            .fg(Color::LightCyan)
+            .bg(Color::Black)
+    );
+    items.push(
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Mixed Molar Mass:", result.props.mm, "g/mol",
+            )
+        )
+            .fg(Color::LightCyan)
+            .bg(Color::Black)
+    );
+    items.push(
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Total Flow:", flow, flow_str,
+            )
+        )
+            .fg(Color::LightCyan)
+            .bg(Color::Black)
+    );
+    for (name, x) in components(&result.comp) {
+        if x > 0.0 {
+            items.push(
+                ListItem::new(
+                    format!("  {:<22} {:.6} {}",
+                        name, x, "mol frac",
+                    )
+                )
+                    .fg(Color::White)
+                    .bg(Color::Black)
+            );
+        }
+    }
+    items
+}
@@ -24,6 +24,27 @@ use ratatui_textarea::TextArea;
 
 use crate::{
     App,
+    blowdown,
+    compare,
+    corrector,
+    departure,
+    exchanger,
+    expander,
+    filling,
+    fugacity,
+    grid,
+    linepack,
+    mixer,
+    nozzle,
+    orifice,
+    pipeline,
+    refstate,
+    relief,
+    storage,
+    sweep,
+    throttle,
+    usm,
+    valve,
     units::PrintUnit, 
 };
 
@@ -143,7 +164,7 @@ pub fn select_units_modal(app: &mut App, frame: &mut Frame, main_area: Rect) {
     .style(Style::new().fg(Color::White).bg(Color::Blue));
 
     let modal_content: Paragraph<'_> = Paragraph::new(
-        format!("Select Unit Type\n1-Pressure  2-Temperature  3-Density  4-Energy  5-Entropy  6-Speed  7-Flow")
+        format!("Select Unit Type\n1-Pressure  2-Temperature  3-Density  4-Energy  5-Entropy  6-Speed  7-Flow  8-Power  9-Viscosity  0-Conductivity")
     )
     .block(Block::new().padding(ratatui::widgets::Padding::uniform(1)));
 
@@ -306,6 +327,72 @@ pub fn flow_units_modal(app: &mut App, frame: &mut Frame, main_area: Rect) {
     frame.render_widget(modal_content, modal_area);
 }
 
+pub fn power_units_modal(app: &mut App, frame: &mut Frame, main_area: Rect) {
+    let modal_width_percent = 60;
+    let modal_height_percent = 20;
+    let modal_area = popup_area(main_area, modal_width_percent, modal_height_percent);
+
+    // Clear the background behind the modal
+    frame.render_widget(Clear, modal_area);
+
+    let modal_block = Block::new()
+    .title("Power Unit Options")
+    .borders(Borders::ALL)
+    .style(Style::new().fg(Color::White).bg(Color::Blue));
+
+    let modal_content: Paragraph<'_> = Paragraph::new(
+        "Select Power Unit\n1-kW   2-hp   3-BTU/hr"
+    )
+    .block(Block::new().padding(ratatui::widgets::Padding::uniform(1)));
+
+    frame.render_widget(modal_block, modal_area);
+    frame.render_widget(modal_content, modal_area);
+}
+
+pub fn viscosity_units_modal(app: &mut App, frame: &mut Frame, main_area: Rect) {
+    let modal_width_percent = 60;
+    let modal_height_percent = 20;
+    let modal_area = popup_area(main_area, modal_width_percent, modal_height_percent);
+
+    // Clear the background behind the modal
+    frame.render_widget(Clear, modal_area);
+
+    let modal_block = Block::new()
+    .title("Viscosity Unit Options")
+    .borders(Borders::ALL)
+    .style(Style::new().fg(Color::White).bg(Color::Blue));
+
+    let modal_content: Paragraph<'_> = Paragraph::new(
+        "Select Viscosity Unit (dynamic / kinematic)\n1-cP / cSt   2-Pa-s / m^2/s   3-lbm/(ft-s) / ft^2/s"
+    )
+    .block(Block::new().padding(ratatui::widgets::Padding::uniform(1)));
+
+    frame.render_widget(modal_block, modal_area);
+    frame.render_widget(modal_content, modal_area);
+}
+
+pub fn conductivity_units_modal(app: &mut App, frame: &mut Frame, main_area: Rect) {
+    let modal_width_percent = 60;
+    let modal_height_percent = 20;
+    let modal_area = popup_area(main_area, modal_width_percent, modal_height_percent);
+
+    // Clear the background behind the modal
+    frame.render_widget(Clear, modal_area);
+
+    let modal_block = Block::new()
+    .title("Thermal Conductivity Unit Options")
+    .borders(Borders::ALL)
+    .style(Style::new().fg(Color::White).bg(Color::Blue));
+
+    let modal_content: Paragraph<'_> = Paragraph::new(
+        "Select Thermal Conductivity Unit\n1-W/(m-K)   2-BTU/(hr-ft-F)"
+    )
+    .block(Block::new().padding(ratatui::widgets::Padding::uniform(1)));
+
+    frame.render_widget(modal_block, modal_area);
+    frame.render_widget(modal_content, modal_area);
+}
+
 pub fn input_speed_modal(app: &mut App, frame: &mut Frame, main_area: Rect) {
     let modal_width_percent = 60;
     let modal_height_percent = 20;
@@ -364,4 +451,395 @@ pub fn wheel_diameter_modal(app: &mut App, frame: &mut Frame, main_area: Rect) {
 
     frame.render_widget(modal_block, modal_area);
     frame.render_widget(modal_content, modal_area);
-}
\ No newline at end of file
+}
+pub fn hydrate_modal(app: &mut App, frame: &mut Frame, main_area: Rect) {
+    let modal_width_percent = 60;
+    let modal_height_percent = 20;
+    let modal_area = popup_area(main_area, modal_width_percent, modal_height_percent);
+
+    // Clear the background behind the modal
+    frame.render_widget(Clear, modal_area);
+
+    let modal_block = Block::new()
+    .title(format!("Hydrate Inhibitor (current: {} {:.1} wt%)", app.inhibitor.name(), app.inhibitor_wt))
+    .borders(Borders::ALL)
+    .style(Style::new().fg(Color::White).bg(Color::Blue));
+
+    let modal_content: Paragraph<'_> = Paragraph::new(
+        "Select Inhibitor\n1-None   2-Methanol   3-MEG"
+    )
+    .block(Block::new().padding(ratatui::widgets::Padding::uniform(1)));
+
+    frame.render_widget(modal_block, modal_area);
+    frame.render_widget(modal_content, modal_area);
+}
+
+pub fn inhibitor_modal(app: &mut App, frame: &mut Frame, main_area: Rect) {
+    let modal_width_percent = 60;
+    let modal_height_percent = 20;
+    let modal_area = popup_area(main_area, modal_width_percent, modal_height_percent);
+
+    // Clear the background behind the modal
+    frame.render_widget(Clear, modal_area);
+
+    let modal_block = Block::new()
+    .title("Inhibitor Concentration")
+    .borders(Borders::ALL)
+    .style(Style::new().fg(Color::White).bg(Color::Blue));
+
+    let modal_content = Paragraph::new(format!("Enter {} concentration in free water (wt%)\n{}", app.inhibitor.name(), app.input_text.lines()[0]))
+    .block(Block::new().padding(ratatui::widgets::Padding::uniform(1)));
+
+    frame.render_widget(modal_block, modal_area);
+    frame.render_widget(modal_content, modal_area);
+}
+
+pub fn tools_modal(app: &mut App, frame: &mut Frame, main_area: Rect) {
+    let modal_width_percent = 60;
+    let modal_height_percent = 40;
+    let modal_area = popup_area(main_area, modal_width_percent, modal_height_percent);
+
+    // Clear the background behind the modal
+    frame.render_widget(Clear, modal_area);
+
+    let modal_block = Block::new()
+    .title("Analysis Tools")
+    .borders(Borders::ALL)
+    .style(Style::new().fg(Color::White).bg(Color::Blue));
+
+    let modal_content: Paragraph<'_> = Paragraph::new(
+        "Select Tool\n1-Throttling (JT)   2-Turboexpander   3-Stream Mixer   4-Heat Exchanger\n5-Orifice Meter   6-Sonic Nozzle   7-USM SOS Check   8-Volume Corrector\n9-Control Valve   R-Relief Valve   P-Pipeline   L-Linepack   B-Blowdown\nF-Tank Filling   S-Storage Inventory   E-EOS Comparison\nD-Departure Functions   H-H/S Reference State   G-Fugacity Table\nT-Property Sweep   K-Lookup Grid"
+    )
+    .block(Block::new().padding(ratatui::widgets::Padding::uniform(1)));
+
+    frame.render_widget(modal_block, modal_area);
+    frame.render_widget(modal_content, modal_area);
+}
+
+// Shared layout for the calculator modals: results list with the hotkeys
+// and the value being entered along the bottom
+fn calculator_modal(frame: &mut Frame, area: Rect, title: &str, items: Vec<ListItem<'_>>, hotkeys: &str, input: Option<&str>) {
+    let modal_width_percent = 70;
+    let modal_height_percent = 70;
+    let modal_area = popup_area(area, modal_width_percent, modal_height_percent);
+
+    // Clear the background behind the modal
+    frame.render_widget(Clear, modal_area);
+
+    let modal_block = Block::new()
+    .title(title.to_string())
+    .borders(Borders::ALL)
+    .style(Style::new().fg(Color::White).bg(Color::Blue));
+
+    let inner_area = modal_block.inner(modal_area);
+    let [list_area, hotkey_area] = Layout::vertical([
+        Constraint::Fill(1),
+        Constraint::Length(2),
+    ])
+    .areas(inner_area);
+
+    let hotkey_text = match input {
+        Some(text) => format!("{}\nEnter value: {}", hotkeys, text),
+        None => hotkeys.to_string(),
+    };
+
+    frame.render_widget(modal_block, modal_area);
+    frame.render_widget(List::new(items), list_area);
+    frame.render_widget(Paragraph::new(hotkey_text), hotkey_area);
+}
+
+// Tool results, calculated again only after the cache has been cleared
+fn cached_items(app: &mut App, run: impl FnOnce(&mut App) -> Vec<ListItem<'static>>) -> Vec<ListItem<'static>> {
+    if app.tool_items.is_none() {
+        app.tool_items = Some(run(app));
+    }
+    app.tool_items.clone().unwrap_or_default()
+}
+
+fn calculator_input(app: &App) -> Option<&str> {
+    if app.input_modal_active {
+        Some(app.input_text.lines()[0].as_str())
+    } else {
+        None
+    }
+}
+
+pub fn throttle_modal(app: &mut App, frame: &mut Frame, area: Rect) {
+    let items = cached_items(app, |app| throttle::run_throttle(app));
+    calculator_modal(
+        frame,
+        area,
+        "Throttling Valve (Isenthalpic Expansion)",
+        items,
+        "1-Downstream Pressure   O-Set Outlet State   Esc-Close",
+        calculator_input(app),
+    );
+}
+
+pub fn expander_modal(app: &mut App, frame: &mut Frame, area: Rect) {
+    let items = cached_items(app, expander::run_expander);
+    let brake_text = if app.expander_brake { "On" } else { "Off" };
+    calculator_modal(
+        frame,
+        area,
+        "Turboexpander",
+        items,
+        &format!("1-Outlet Pressure   2-Efficiency   B-Brake Compressor ({})   Esc-Close", brake_text),
+        calculator_input(app),
+    );
+}
+
+pub fn mixer_modal(app: &mut App, frame: &mut Frame, area: Rect) {
+    let items = cached_items(app, |app| mixer::run_mixer(app));
+    calculator_modal(
+        frame,
+        area,
+        "Adiabatic Stream Mixer",
+        items,
+        "A-Add Current State as Stream   D-Remove Last   C-Clear   L-Load Mixture as Current   Esc-Close",
+        None,
+    );
+}
+
+pub fn exchanger_modal(app: &mut App, frame: &mut Frame, area: Rect) {
+    let items = cached_items(app, |app| exchanger::run_exchanger(app));
+    calculator_modal(
+        frame,
+        area,
+        "Heat Exchanger Duty (Inlet to Outlet State)",
+        items,
+        "1-Utility Inlet Temp   2-Utility Outlet Temp   Esc-Close",
+        calculator_input(app),
+    );
+}
+
+pub fn orifice_modal(app: &mut App, frame: &mut Frame, area: Rect) {
+    let items = cached_items(app, |app| orifice::run_orifice(app));
+    calculator_modal(
+        frame,
+        area,
+        "Orifice Meter (ISO 5167 / AGA 3) at Current State",
+        items,
+        "1-Pipe Diameter   2-Bore Diameter   3-Differential Press   T-Tap Type   Esc-Close",
+        calculator_input(app),
+    );
+}
+
+pub fn nozzle_modal(app: &mut App, frame: &mut Frame, area: Rect) {
+    let items = cached_items(app, |app| nozzle::run_nozzle(app));
+    calculator_modal(
+        frame,
+        area,
+        "Critical Flow Venturi (ISO 9300) from Current State",
+        items,
+        "1-Throat Diameter   2-Discharge Coeff   Esc-Close",
+        calculator_input(app),
+    );
+}
+
+pub fn usm_modal(app: &mut App, frame: &mut Frame, area: Rect) {
+    let items = cached_items(app, |app| usm::run_usm(app));
+    calculator_modal(
+        frame,
+        area,
+        "Ultrasonic Meter Speed of Sound Verification (AGA 10) at Current State",
+        items,
+        "1-Add Path SOS   2-Tolerance   D-Remove Path   C-Clear Paths   L-Log Check   X-Clear Log   Esc-Close",
+        calculator_input(app),
+    );
+}
+
+pub fn corrector_modal(app: &mut App, frame: &mut Frame, area: Rect) {
+    let items = cached_items(app, |app| corrector::run_corrector(app));
+    calculator_modal(
+        frame,
+        area,
+        "Meter Volume Correction (AGA 7) at Current State",
+        items,
+        "1-Actual Volume   2-Base Pressure   3-Base Temperature   Esc-Close",
+        calculator_input(app),
+    );
+}
+
+pub fn valve_modal(app: &mut App, frame: &mut Frame, area: Rect) {
+    let items = cached_items(app, |app| valve::run_valve(app));
+    calculator_modal(
+        frame,
+        area,
+        "Control Valve Sizing (IEC 60534-2-1)",
+        items,
+        "1-Outlet Pressure   2-xT   3-Fp   4-Outlet Pipe Diameter   Esc-Close",
+        calculator_input(app),
+    );
+}
+
+pub fn relief_modal(app: &mut App, frame: &mut Frame, area: Rect) {
+    let items = cached_items(app, |app| relief::run_relief(app));
+    calculator_modal(
+        frame,
+        area,
+        "Relief Valve Sizing (API 520 Part I / API 526)",
+        items,
+        "1-Set Pressure   2-Overpressure   3-Relieving Temp   4-Back Pressure   5-Kd   Esc-Close",
+        calculator_input(app),
+    );
+}
+
+pub fn pipeline_modal(app: &mut App, frame: &mut Frame, area: Rect) {
+    let items = cached_items(app, |app| pipeline::run_pipeline(app));
+    calculator_modal(
+        frame,
+        area,
+        "Pipeline Pressure Drop and Erosional Velocity (API RP 14E)",
+        items,
+        "1-Length   2-Diameter   3-Roughness   4-Elevation   5-C Factor   E-Equation   Esc-Close",
+        calculator_input(app),
+    );
+}
+
+pub fn linepack_modal(app: &mut App, frame: &mut Frame, area: Rect) {
+    let items = cached_items(app, |app| linepack::run_linepack(app));
+    calculator_modal(
+        frame,
+        area,
+        "Pipeline Linepack (Inlet to Outlet State)",
+        items,
+        "1-Length   2-Diameter   S-Save Reference Case   X-Clear Reference   Esc-Close",
+        calculator_input(app),
+    );
+}
+
+pub fn blowdown_modal(app: &mut App, frame: &mut Frame, area: Rect) {
+    let items = cached_items(app, |app| blowdown::run_blowdown(app));
+    calculator_modal(
+        frame,
+        area,
+        "Vessel Blowdown from Current State",
+        items,
+        "1-Volume   2-Orifice   3-Cd   4-Back Press   5-Target Press   6-Wall Mass   7-Film Coeff   Esc-Close",
+        calculator_input(app),
+    );
+}
+
+pub fn filling_modal(app: &mut App, frame: &mut Frame, area: Rect) {
+    let items = cached_items(app, |app| filling::run_filling(app));
+    calculator_modal(
+        frame,
+        area,
+        "Tank Filling from Current State (Source)",
+        items,
+        "1-Volume  2-Init Press  3-Init Temp  4-Target Press  5-Rate  6-Orifice  7-Cd  8-Wall Mass  9-Film Coeff  R-Mode  Esc-Close",
+        calculator_input(app),
+    );
+}
+
+pub fn storage_modal(app: &mut App, frame: &mut Frame, area: Rect) {
+    let items = cached_items(app, |app| storage::run_storage(app));
+    calculator_modal(
+        frame,
+        area,
+        "Storage Inventory at Current State",
+        items,
+        "1-Volume   2-Units   3-Max Pressure   4-Min Pressure   5-Target Quantity   Esc-Close",
+        calculator_input(app),
+    );
+}
+
+pub fn compare_modal(app: &mut App, frame: &mut Frame, area: Rect) {
+    let items = cached_items(app, compare::run_compare);
+    calculator_modal(
+        frame,
+        area,
+        "Equation of State Comparison",
+        items,
+        "1-Highlight Threshold   M-Compared Model   Up/Down-Scroll   Esc-Close",
+        calculator_input(app),
+    );
+}
+
+pub fn departure_modal(app: &mut App, frame: &mut Frame, area: Rect) {
+    let items = cached_items(app, departure::run_departure);
+    calculator_modal(
+        frame,
+        area,
+        "Ideal Gas Reference and Departure Functions",
+        items,
+        "Up/Down-Scroll   Esc-Close",
+        calculator_input(app),
+    );
+}
+
+pub fn refstate_modal(app: &mut App, frame: &mut Frame, area: Rect) {
+    let items = cached_items(app, |app| refstate::run_refstate(app));
+    calculator_modal(
+        frame,
+        area,
+        "Enthalpy and Entropy Reference State",
+        items,
+        "C-Convention   1-Datum Pressure   2-Datum Temperature   Esc-Close",
+        calculator_input(app),
+    );
+}
+
+pub fn fugacity_modal(app: &mut App, frame: &mut Frame, area: Rect) {
+    let items = cached_items(app, fugacity::run_fugacity);
+    calculator_modal(
+        frame,
+        area,
+        "Fugacity Coefficients and Chemical Potentials",
+        items,
+        "Up/Down-Scroll   Esc-Close",
+        calculator_input(app),
+    );
+}
+
+pub fn sweep_modal(app: &mut App, frame: &mut Frame, area: Rect) {
+    let modal_area = popup_area(area, 90, 80);
+
+    // Clear the background behind the modal
+    frame.render_widget(Clear, modal_area);
+
+    let modal_block = Block::new()
+    .title("Property Sweep Table")
+    .borders(Borders::ALL)
+    .style(Style::new().fg(Color::White).bg(Color::Blue));
+
+    let result = match app.sweep_result.take() {
+        Some(result) => result,
+        None => sweep::sweep(app),
+    };
+    let items = sweep::run_sweep(app, &result);
+    let table = sweep::sweep_table(app, &result);
+
+    let inner_area = modal_block.inner(modal_area);
+    let [list_area, table_area, hotkey_area] = Layout::vertical([
+        Constraint::Length(items.len() as u16),
+        Constraint::Fill(1),
+        Constraint::Length(3),
+    ])
+    .areas(inner_area);
+
+    let hotkeys = "1-Start   2-Stop   3-Step   V-Variable   C-Component   X-Export CSV   Esc-Close\nLeft/Right-Output Property   Space-Select Output   Up/Down-Scroll";
+    let hotkey_text = match calculator_input(app) {
+        Some(text) => format!("{}\nEnter value: {}", hotkeys, text),
+        None => hotkeys.to_string(),
+    };
+
+    frame.render_widget(modal_block, modal_area);
+    frame.render_widget(List::new(items), list_area);
+    frame.render_widget(table, table_area);
+    frame.render_widget(Paragraph::new(hotkey_text), hotkey_area);
+    app.sweep_result = Some(result);
+}
+
+pub fn grid_modal(app: &mut App, frame: &mut Frame, area: Rect) {
+    let items = cached_items(app, |app| grid::run_grid(app));
+    calculator_modal(
+        frame,
+        area,
+        "P-T Lookup Grid (Density, Z, Speed of Sound)",
+        items,
+        "1-6-Grid Range   F-Format   X-Export   Esc-Close",
+        calculator_input(app),
+    );
+}
@@ -0,0 +1,243 @@
+use std::f64::consts::PI;
+
+use ratatui::widgets::ListItem;
+use ratatui::
+    style::{
+        Color,
+        Stylize
+    };
+
+use crate::eos::new_eos;
+use crate::flash::{
+    self,
+    GasProps,
+};
+use crate::units::{
+    self,
+    PrintUnit,
+};
+use crate::App;
+
+// Gas constant in J/(mol-K)
+const R: f64 = 8.314462618;
+const MAX_ITER: usize = 100;
+
+pub struct NozzleResult {
+    pub stagnation: GasProps,
+    pub throat: GasProps,
+    // Real gas critical flow factor
+    pub c_star: f64,
+    // Ideal gas critical flow factor from the zero pressure cp/cv
+    pub c_star_ideal: f64,
+    // Mass flux at the throat in kg/(m^2-s)
+    pub mass_flux: f64,
+}
+
+// Velocity squared (m^2/s^2) reached by expanding from the stagnation
+// enthalpy to the state, less the local speed of sound squared
+fn sonic_residual(stagnation: &GasProps, state: &GasProps) -> f64 {
+    2.0 * (stagnation.h - state.h) / state.mm * 1000.0 - state.w * state.w
+}
+
+// Throat state on the stagnation isentrope where the velocity equals the
+// local speed of sound, found by bisection on pressure
+pub fn throat_state(app: &App, stagnation: &GasProps) -> GasProps {
+    let mut p_low = 0.2 * stagnation.p;
+    let mut p_high = stagnation.p;
+    let mut eos = new_eos(app.model, &app.gas_comp);
+    let mut throat = flash::solve_ps(eos.as_mut(), p_high, stagnation.s, stagnation.t);
+    for _ in 0..MAX_ITER {
+        let p_mid = (p_low + p_high) / 2.0;
+        throat = flash::solve_ps(eos.as_mut(), p_mid, stagnation.s, throat.t);
+        if sonic_residual(stagnation, &throat) > 0.0 {
+            p_low = p_mid;
+        } else {
+            p_high = p_mid;
+        }
+        if p_high - p_low < 1.0e-9 * stagnation.p {
+            break;
+        }
+    }
+    throat
+}
+
+// Nozzle stagnation conditions are the current state
+pub fn critical_flow(app: &App) -> NozzleResult {
+    let (p0, t0) = (app.cur_state.p, app.cur_state.t);
+    let stagnation = flash::props_pt(&app.gas_comp, app.model, p0, t0);
+    let throat = throat_state(app, &stagnation);
+    let mass_flux = throat.d * throat.mm * throat.w;
+    // C* = q_m sqrt(R T0 / M) / (A p0)
+    let c_star = mass_flux * (R * t0 / stagnation.mm * 1000.0).sqrt() / (p0 * 1000.0);
+    let ideal = flash::props_pt(&app.gas_comp, app.model, 1.0e-3, t0);
+    let k = ideal.cp / ideal.cv;
+    let c_star_ideal = (k * (2.0 / (k + 1.0)).powf((k + 1.0) / (k - 1.0))).sqrt();
+    NozzleResult {
+        stagnation,
+        throat,
+        c_star,
+        c_star_ideal,
+        mass_flux,
+    }
+}
+
+// Mass flow in kg/s for the throat diameter and discharge coefficient
+pub fn nozzle_flow(app: &App, result: &NozzleResult) -> f64 {
+    app.nozzle_cd * PI / 4.0 * app.nozzle_throat_d * app.nozzle_throat_d * result.mass_flux
+}
+
+pub fn set_param(app: &mut App, val: f64) {
+    match app.input_param {
+        1 => app.nozzle_throat_d = units::set_length(val, app.units.length),
+        2 => app.nozzle_cd = val,
+        _ => {}
+    }
+}
+
+pub fn run_nozzle(app: &App) -> Vec<ListItem<'static>> {
+    let result = critical_flow(app);
+    let flow = nozzle_flow(app, &result);
+    let p_str = app.units.pressure.print_unit();
+    let t_str = app.units.temp.print_unit();
+    let speed_str = app.units.speed.print_unit();
+    let mass_unit = app.units.flow.mass_unit();
+
+    let mut items = vec![
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "1-Throat Diameter:", units::get_length(app.nozzle_throat_d, app.units.length), app.units.length.print_unit(),
+            )
+        )
+            .fg(Color::LightYellow)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "2-Discharge Coeff:", app.nozzle_cd, "[]",
+            )
+        )
+            .fg(Color::LightYellow)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Stagnation Press:", units::get_pressure(result.stagnation.p, app.units.pressure), p_str,
+            )
+        )
+            .fg(Color::White)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Stagnation Temp:", units::get_temperature(result.stagnation.t, app.units.temp), t_str,
+            )
+        )
+            .fg(Color::White)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Throat Press:", units::get_pressure(result.throat.p, app.units.pressure), p_str,
+            )
+        )
+            .fg(Color::LightCyan)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Throat Temp:", units::get_temperature(result.throat.t, app.units.temp), t_str,
+            )
+        )
+            .fg(Color::LightCyan)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Throat Speed of Sound:", units::get_speed(result.throat.w, app.units.speed), speed_str,
+            )
+        )
+            .fg(Color::LightCyan)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Critical Press Ratio:", result.throat.p / result.stagnation.p, "[]",
+            )
+        )
+            .fg(Color::LightCyan)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.6} {}",
+                "Critical Flow Factor C*:", result.c_star, "[]",
+            )
+        )
+            .fg(Color::LightCyan)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.6} {}",
+                "Ideal Gas C*i:", result.c_star_ideal, "[]",
+            )
+        )
+            .fg(Color::White)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Mass Flow:", units::get_flow(flow, mass_unit, &app.gas_comp, app.stp_60_F, app.model), mass_unit.print_unit(),
+            )
+        )
+            .fg(Color::LightCyan)
+            .bg(Color::Black),
+    ];
+    for std_unit in app.units.flow.std_volume_units() {
+        items.push(
+            ListItem::new(
+                format!("{:<24} {:.4} {}",
+                    "Std Volume Flow:", units::get_flow(flow, *std_unit, &app.gas_comp, app.stp_60_F, app.model), std_unit.print_unit(),
+                )
+            )
+                .fg(Color::LightCyan)
+                .bg(Color::Black)
+        );
+    }
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eos::Model;
+    use crate::gas::{
+        get_gas_comp,
+        Gas,
+    };
+
+    fn air_app(model: Model, p: f64, t: f64) -> App {
+        let gas_comp = get_gas_comp(Gas::Air);
+        App {
+            cur_state: flash::props_pt(&gas_comp, model, p, t),
+            gas_comp,
+            model,
+            ..Default::default()
+        }
+    }
+
+    // Along an ideal gas isentrope the bisection lands on the closed form,
+    // C* = 0.6847 for k = 1.4
+    #[test]
+    fn ideal_gas_critical_flow_factor() {
+        let result = critical_flow(&air_app(Model::IdealGas, 1000.0, 300.0));
+        assert!((result.c_star / result.c_star_ideal - 1.0).abs() < 1.0e-3, "{} {}", result.c_star, result.c_star_ideal);
+        assert!((result.c_star - 0.6847).abs() < 1.0e-3, "{}", result.c_star);
+    }
+
+    // Dry air at 300 K and 10 MPa stagnation, C* = 0.7100 from the real gas
+    // tables of ISO 9300 Annex C (after Johnson)
+    #[test]
+    fn real_gas_critical_flow_factor() {
+        let result = critical_flow(&air_app(Model::Gerg2008, 10000.0, 300.0));
+        assert!((result.c_star - 0.7100).abs() < 3.0e-3, "{}", result.c_star);
+    }
+}
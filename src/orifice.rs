@@ -0,0 +1,385 @@
+use std::f64::consts::PI;
+
+use ratatui::widgets::ListItem;
+use ratatui::
+    style::{
+        Color,
+        Stylize
+    };
+
+use crate::transport;
+use crate::units::{
+    self,
+    PrintUnit,
+};
+use crate::App;
+
+const MAX_ITER: usize = 50;
+
+#[derive(Clone, Copy)]
+pub enum Tap {
+    Corner,
+    Flange,
+    DD2,
+}
+impl Tap {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Tap::Corner => "Corner",
+            Tap::Flange => "Flange",
+            Tap::DD2 => "D and D/2",
+        }
+    }
+
+    pub fn next(&self) -> Tap {
+        match self {
+            Tap::Corner => Tap::Flange,
+            Tap::Flange => Tap::DD2,
+            Tap::DD2 => Tap::Corner,
+        }
+    }
+
+    // Upstream and downstream tap spacing ratios L1 and L2' for pipe diameter (m)
+    fn spacing(&self, pipe_d: f64) -> (f64, f64) {
+        match self {
+            Tap::Corner => (0.0, 0.0),
+            Tap::Flange => (0.0254 / pipe_d, 0.0254 / pipe_d),
+            Tap::DD2 => (1.0, 0.47),
+        }
+    }
+}
+
+// Upstream conditions taken from the current state
+pub struct Upstream {
+    pub p: f64,
+    pub t: f64,
+    // Density in mol/l
+    pub d: f64,
+    pub mm: f64,
+    pub kappa: f64,
+    // Dynamic viscosity in Pa-s
+    pub mu: f64,
+}
+
+pub struct OrificeResult {
+    pub beta: f64,
+    pub cd: f64,
+    pub epsilon: f64,
+    pub reynolds: f64,
+    // Mass flow in kg/s
+    pub flow: f64,
+}
+
+pub fn upstream(app: &App) -> Upstream {
+    let s = &app.cur_state;
+    let (p, t, d, mm, kappa, cp) = (s.p, s.t, s.d, s.mm, s.kappa, s.cp);
+    let tp = transport::transport_properties(&app.gas_comp, app.model, t, d, mm, cp);
+    Upstream {
+        p,
+        t,
+        d,
+        mm,
+        kappa,
+        mu: tp.viscosity,
+    }
+}
+
+// Reader-Harris/Gallagher discharge coefficient (ISO 5167-2)
+pub fn discharge_coeff(beta: f64, reynolds: f64, pipe_d: f64, tap: Tap) -> f64 {
+    let (l1, l2) = tap.spacing(pipe_d);
+    let a = (19000.0 * beta / reynolds).powf(0.8);
+    let m2 = 2.0 * l2 / (1.0 - beta);
+    let beta4 = beta.powi(4);
+    let mut cd = 0.5961 + 0.0261 * beta * beta - 0.216 * beta.powi(8)
+        + 0.000521 * (1.0e6 * beta / reynolds).powf(0.7)
+        + (0.0188 + 0.0063 * a) * beta.powf(3.5) * (1.0e6 / reynolds).powf(0.3)
+        + (0.043 + 0.080 * (-10.0 * l1).exp() - 0.123 * (-7.0 * l1).exp()) * (1.0 - 0.11 * a) * beta4 / (1.0 - beta4)
+        - 0.031 * (m2 - 0.8 * m2.powf(1.1)) * beta.powf(1.3);
+    // Small pipe correction below 71.12 mm
+    let pipe_mm = pipe_d * 1000.0;
+    if pipe_mm < 71.12 {
+        cd += 0.011 * (0.75 - beta) * (2.8 - pipe_mm / 25.4);
+    }
+    cd
+}
+
+// Expansibility factor for pressure taps (ISO 5167-2)
+pub fn expansibility(beta: f64, p1: f64, dp: f64, kappa: f64) -> f64 {
+    let p2 = p1 - dp;
+    if p2 <= 0.0 {
+        return 0.0
+    }
+    1.0 - (0.351 + 0.256 * beta.powi(4) + 0.93 * beta.powi(8)) * (1.0 - (p2 / p1).powf(1.0 / kappa))
+}
+
+// Mass flow through the orifice for a differential pressure (kPa), iterating
+// the discharge coefficient on the pipe Reynolds number
+pub fn orifice_flow(app: &App, up: &Upstream, dp: f64) -> OrificeResult {
+    let beta = app.orifice_bore_d / app.orifice_pipe_d;
+    let epsilon = expansibility(beta, up.p, dp, up.kappa);
+    let area = PI / 4.0 * app.orifice_bore_d * app.orifice_bore_d;
+    let flow_factor = epsilon * area * (2.0 * dp * 1000.0 * up.d * up.mm).sqrt() / (1.0 - beta.powi(4)).sqrt();
+    let mut cd = 0.6;
+    let mut flow = cd * flow_factor;
+    let mut reynolds = 4.0 * flow / (PI * up.mu * app.orifice_pipe_d);
+    for _ in 0..MAX_ITER {
+        if reynolds <= 0.0 {
+            break;
+        }
+        let cd_new = discharge_coeff(beta, reynolds, app.orifice_pipe_d, app.orifice_tap);
+        flow = cd_new * flow_factor;
+        reynolds = 4.0 * flow / (PI * up.mu * app.orifice_pipe_d);
+        if (cd_new - cd).abs() < 1.0e-10 {
+            cd = cd_new;
+            break;
+        }
+        cd = cd_new;
+    }
+    OrificeResult {
+        beta,
+        cd,
+        epsilon,
+        reynolds,
+        flow,
+    }
+}
+
+// Differential pressure (kPa) that passes the mass flow (kg/s)
+pub fn orifice_dp(app: &App, up: &Upstream, flow: f64) -> f64 {
+    if flow <= 0.0 {
+        return 0.0
+    }
+    let mut dp = app.orifice_dp.max(1.0);
+    for _ in 0..MAX_ITER {
+        let result = orifice_flow(app, up, dp);
+        if result.flow <= 0.0 {
+            return 0.0
+        }
+        let dp_new = dp * (flow / result.flow).powi(2);
+        if (dp_new - dp).abs() < 1.0e-9 * dp {
+            return dp_new
+        }
+        dp = dp_new;
+    }
+    dp
+}
+
+pub fn set_param(app: &mut App, val: f64) {
+    match app.input_param {
+        1 => app.orifice_pipe_d = units::set_length(val, app.units.length),
+        2 => app.orifice_bore_d = units::set_length(val, app.units.length),
+        3 => app.orifice_dp = units::set_pressure(val, app.units.pressure),
+        _ => {}
+    }
+}
+
+pub fn run_orifice(app: &App) -> Vec<ListItem<'static>> {
+    let p_str = app.units.pressure.print_unit();
+    let length_str = app.units.length.print_unit();
+    let flow_str = app.units.flow.print_unit();
+    let mass_unit = app.units.flow.mass_unit();
+
+    let mut items = vec![
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "1-Pipe Diameter:", units::get_length(app.orifice_pipe_d, app.units.length), length_str,
+            )
+        )
+            .fg(Color::LightYellow)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "2-Bore Diameter:", units::get_length(app.orifice_bore_d, app.units.length), length_str,
+            )
+        )
+            .fg(Color::LightYellow)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "3-Differential Press:", units::get_pressure(app.orifice_dp, app.units.pressure), p_str,
+            )
+        )
+            .fg(Color::LightYellow)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {}",
+                "T-Tap Type:", app.orifice_tap.name(),
+            )
+        )
+            .fg(Color::LightYellow)
+            .bg(Color::Black),
+    ];
+
+    let beta = app.orifice_bore_d / app.orifice_pipe_d;
+    if !(0.1..=0.75).contains(&beta) {
+        items.push(
+            ListItem::new(format!("Diameter ratio {:.4} is outside the 0.1 to 0.75 range of the standard", beta))
+                .fg(Color::Red)
+                .bg(Color::Black)
+        );
+        return items
+    }
+
+    let up = upstream(app);
+    let result = orifice_flow(app, &up, app.orifice_dp);
+    let dp_flow = orifice_dp(app, &up, app.flow_val);
+
+    items.push(
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Diameter Ratio:", result.beta, "[]",
+            )
+        )
+            .fg(Color::White)
+            .bg(Color::Black)
+    );
+    items.push(
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Upstream Press:", units::get_pressure(up.p, app.units.pressure), p_str,
+            )
+        )
+            .fg(Color::White)
+            .bg(Color::Black)
+    );
+    items.push(
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Upstream Temp:", units::get_temperature(up.t, app.units.temp), app.units.temp.print_unit(),
+            )
+        )
+            .fg(Color::White)
+            .bg(Color::Black)
+    );
+    items.push(
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Upstream Density:", units::get_density(up.d, app.units.density, up.mm), app.units.density.print_unit(),
+            )
+        )
+            .fg(Color::White)
+            .bg(Color::Black)
+    );
+    items.push(
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Isentropic Exponent:", up.kappa, "[]",
+            )
+        )
+            .fg(Color::White)
+            .bg(Color::Black)
+    );
+    items.push(
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Discharge Coeff:", result.cd, "[]",
+            )
+        )
+            .fg(Color::LightCyan)
+            .bg(Color::Black)
+    );
+    items.push(
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Expansibility:", result.epsilon, "[]",
+            )
+        )
+            .fg(Color::LightCyan)
+            .bg(Color::Black)
+    );
+    items.push(
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Reynolds No:", result.reynolds, "[]",
+            )
+        )
+            .fg(Color::LightCyan)
+            .bg(Color::Black)
+    );
+    items.push(
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Mass Flow:", units::get_flow(result.flow, mass_unit, &app.gas_comp, app.stp_60_F, app.model), mass_unit.print_unit(),
+            )
+        )
+            .fg(Color::LightCyan)
+            .bg(Color::Black)
+    );
+    for std_unit in app.units.flow.std_volume_units() {
+        items.push(
+            ListItem::new(
+                format!("{:<24} {:.4} {}",
+                    "Std Volume Flow:", units::get_flow(result.flow, *std_unit, &app.gas_comp, app.stp_60_F, app.model), std_unit.print_unit(),
+                )
+            )
+                .fg(Color::LightCyan)
+                .bg(Color::Black)
+        );
+    }
+    items.push(
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Current Flow:", units::get_flow(app.flow_val, app.units.flow, &app.gas_comp, app.stp_60_F, app.model), flow_str,
+            )
+        )
+            .fg(Color::White)
+            .bg(Color::Black)
+    );
+    items.push(
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "DP at Current Flow:", units::get_pressure(dp_flow, app.units.pressure), p_str,
+            )
+        )
+            .fg(Color::LightCyan)
+            .bg(Color::Black)
+    );
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ISO 5167-2 Annex A tables for corner and D and D/2 tappings, beta 0.5
+    // at pipe Reynolds numbers 1e5, 1e6 and 1e7
+    #[test]
+    fn discharge_coeff_tables() {
+        for (tap, expected) in [
+            (Tap::Corner, [0.6069, 0.6038, 0.6027]),
+            (Tap::DD2, [0.6062, 0.6031, 0.6020]),
+        ] {
+            for (reynolds, cd) in [1.0e5, 1.0e6, 1.0e7].iter().zip(expected) {
+                let result = discharge_coeff(0.5, *reynolds, 0.1, tap);
+                assert!((result - cd).abs() < 5.0e-5, "{} at Re {}: {}", tap.name(), reynolds, result);
+            }
+        }
+    }
+
+    #[test]
+    fn expansibility_factor() {
+        assert_eq!(expansibility(0.75, 1000.0, 0.0, 1.4), 1.0);
+        // Pressure ratio 0.75: 1 - 0.52510 (1 - 0.75^(1/1.4))
+        assert!((expansibility(0.75, 1000.0, 250.0, 1.4) - 0.90246).abs() < 1.0e-5);
+        assert_eq!(expansibility(0.75, 1000.0, 1000.0, 1.4), 0.0);
+    }
+
+    // The inverse solve recovers the differential pressure from the flow
+    #[test]
+    fn differential_pressure_from_flow() {
+        let app = App::default();
+        let up = Upstream {
+            p: 5000.0,
+            t: 288.15,
+            d: 2.2,
+            mm: 17.0,
+            kappa: 1.3,
+            mu: 1.1e-5,
+        };
+        let result = orifice_flow(&app, &up, 40.0);
+        assert!((orifice_dp(&app, &up, result.flow) - 40.0).abs() < 1.0e-6);
+    }
+}
@@ -0,0 +1,366 @@
+use std::f64::consts::PI;
+
+use ratatui::widgets::ListItem;
+use ratatui::
+    style::{
+        Color,
+        Stylize
+    };
+
+use crate::eos::{
+    new_eos,
+    Eos,
+};
+use crate::flash::GasProps;
+use crate::hydrate::gas_gravity;
+use crate::throttle;
+use crate::transport;
+use crate::units::{
+    self,
+    PrintUnit,
+};
+use crate::App;
+
+// Base conditions for the standard volume flow in the flow equations
+const BASE_P: f64 = 101.325;
+const BASE_T: f64 = 288.15;
+const GRAVITY: f64 = 9.80665;
+const SEGMENTS: usize = 50;
+const PROFILE_POINTS: usize = 10;
+const MAX_ITER: usize = 50;
+
+#[derive(Clone, Copy)]
+pub enum Equation {
+    Segmented,
+    GeneralFlow,
+    AgaTurbulent,
+    Weymouth,
+    PanhandleA,
+    PanhandleB,
+}
+impl Equation {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Equation::Segmented => "Segment March (EOS)",
+            Equation::GeneralFlow => "General Flow (Colebrook)",
+            Equation::AgaTurbulent => "AGA Fully Turbulent",
+            Equation::Weymouth => "Weymouth",
+            Equation::PanhandleA => "Panhandle A",
+            Equation::PanhandleB => "Panhandle B",
+        }
+    }
+
+    pub fn next(&self) -> Equation {
+        match self {
+            Equation::Segmented => Equation::GeneralFlow,
+            Equation::GeneralFlow => Equation::AgaTurbulent,
+            Equation::AgaTurbulent => Equation::Weymouth,
+            Equation::Weymouth => Equation::PanhandleA,
+            Equation::PanhandleA => Equation::PanhandleB,
+            Equation::PanhandleB => Equation::Segmented,
+        }
+    }
+}
+
+// Point along the line: distance (m), pressure (kPa), velocity (m/s) and
+// the ratio of velocity to the API RP 14E erosional velocity
+pub struct ProfilePoint {
+    pub x: f64,
+    pub p: f64,
+    pub velocity: f64,
+    pub erosional_ratio: f64,
+}
+
+pub struct PipelineResult {
+    pub inlet: GasProps,
+    pub p2: f64,
+    pub z_avg: f64,
+    pub reynolds: f64,
+    pub friction: f64,
+    pub profile: Vec<ProfilePoint>,
+}
+
+// Darcy friction factor from the Colebrook-White equation
+pub fn colebrook(reynolds: f64, rel_roughness: f64) -> f64 {
+    if reynolds < 2000.0 {
+        return 64.0 / reynolds.max(1.0)
+    }
+    let mut inv_sqrt_f: f64 = 8.0;
+    for _ in 0..MAX_ITER {
+        let next = -2.0 * (rel_roughness / 3.7 + 2.51 * inv_sqrt_f / reynolds).log10();
+        if (next - inv_sqrt_f).abs() < 1.0e-12 {
+            inv_sqrt_f = next;
+            break;
+        }
+        inv_sqrt_f = next;
+    }
+    1.0 / (inv_sqrt_f * inv_sqrt_f)
+}
+
+// API RP 14E erosional velocity (m/s) for density (kg/m^3)
+pub fn erosional_velocity(c_factor: f64, rho: f64) -> f64 {
+    1.2199 * c_factor / rho.sqrt()
+}
+
+fn area(app: &App) -> f64 {
+    PI / 4.0 * app.pipeline_d * app.pipeline_d
+}
+
+fn profile_point(app: &App, eos: &mut dyn Eos, t: f64, x: f64, p: f64) -> ProfilePoint {
+    let props = eos.props_pt(p, t);
+    let rho = props.d * props.mm;
+    let velocity = app.flow_val / (rho * area(app));
+    ProfilePoint {
+        x,
+        p,
+        velocity,
+        erosional_ratio: velocity / erosional_velocity(app.pipeline_c_factor, rho),
+    }
+}
+
+// Isothermal march with the EOS density, viscosity and Colebrook friction
+fn march(app: &App, inlet: GasProps, reynolds_inlet: f64) -> PipelineResult {
+    let dx = app.pipeline_length / SEGMENTS as f64;
+    let dz = app.pipeline_dz / SEGMENTS as f64;
+    let a = area(app);
+    let mut p = inlet.p;
+    let mut z_sum = 0.0;
+    let mut eos = new_eos(app.model, &app.gas_comp);
+    let mut profile = vec![profile_point(app, eos.as_mut(), inlet.t, 0.0, p)];
+    let mut friction = colebrook(reynolds_inlet, app.pipeline_roughness / app.pipeline_d);
+    for i in 0..SEGMENTS {
+        let props = eos.props_pt(p, inlet.t);
+        let rho = props.d * props.mm;
+        let mu = transport::transport_properties(&app.gas_comp, app.model, props.t, props.d, props.mm, props.cp).viscosity;
+        let velocity = app.flow_val / (rho * a);
+        let reynolds = rho * velocity * app.pipeline_d / mu;
+        friction = colebrook(reynolds, app.pipeline_roughness / app.pipeline_d);
+        let dp = friction * dx / app.pipeline_d * rho * velocity * velocity / 2.0 + rho * GRAVITY * dz;
+        p -= dp / 1000.0;
+        z_sum += props.z;
+        if p <= 0.0 {
+            break;
+        }
+        if (i + 1) % (SEGMENTS / PROFILE_POINTS) == 0 {
+            profile.push(profile_point(app, eos.as_mut(), inlet.t, dx * (i + 1) as f64, p));
+        }
+    }
+    PipelineResult {
+        inlet,
+        p2: p,
+        z_avg: z_sum / SEGMENTS as f64,
+        reynolds: reynolds_inlet,
+        friction,
+        profile,
+    }
+}
+
+// Outlet pressure from a flow equation written in SI units as
+// Q = a (Tb/Pb)^b [(P1^2 - e^s P2^2) / (G^c Tf Le Z)]^n D^m
+// with Q in std m^3/day, P in kPa, Tf in K, Le in km and D in mm
+fn flow_equation(app: &App, inlet: GasProps, reynolds: f64) -> PipelineResult {
+    let friction = colebrook(reynolds, app.pipeline_roughness / app.pipeline_d);
+    let (a, b, c, n, m) = match app.pipeline_eq {
+        Equation::GeneralFlow => (1.1494e-3 / friction.sqrt(), 1.0, 1.0, 0.5, 2.5),
+        Equation::AgaTurbulent => {
+            let transmission = 4.0 * (3.7 * app.pipeline_d / app.pipeline_roughness).log10();
+            (5.747e-4 * transmission, 1.0, 1.0, 0.5, 2.5)
+        },
+        Equation::Weymouth => (3.7435e-3, 1.0, 1.0, 0.5, 2.667),
+        Equation::PanhandleA => (4.5965e-3, 1.0788, 0.8539, 0.5394, 2.6182),
+        Equation::PanhandleB => (1.002e-2, 1.02, 0.961, 0.51, 2.53),
+        Equation::Segmented => (0.0, 0.0, 0.0, 0.0, 0.0),
+    };
+    let mut eos = new_eos(app.model, &app.gas_comp);
+    let base = eos.props_pt(BASE_P, BASE_T);
+    let q_std = app.flow_val / (base.d * base.mm) * 86400.0;
+    let gravity = gas_gravity(inlet.mm);
+    let d_mm = app.pipeline_d * 1000.0;
+    let length_km = app.pipeline_length / 1000.0;
+    let p1 = inlet.p;
+
+    let mut p2 = p1;
+    let mut z_avg = inlet.z;
+    for _ in 0..MAX_ITER {
+        // Elevation correction
+        let s = 0.0684 * gravity * app.pipeline_dz / (inlet.t * z_avg);
+        let le = if s.abs() > 1.0e-9 { length_km * (s.exp() - 1.0) / s } else { length_km };
+        let dp2 = (q_std / (a * (BASE_T / BASE_P).powf(b) * d_mm.powf(m))).powf(1.0 / n)
+            * gravity.powf(c) * inlet.t * le * z_avg;
+        let p2_sq = (p1 * p1 - dp2) / s.exp();
+        if p2_sq <= 0.0 {
+            p2 = 0.0;
+            break;
+        }
+        let p2_new = p2_sq.sqrt();
+        let p_avg = 2.0 / 3.0 * (p1 + p2_new - p1 * p2_new / (p1 + p2_new));
+        z_avg = eos.props_pt(p_avg, inlet.t).z;
+        if (p2_new - p2).abs() < 1.0e-6 {
+            p2 = p2_new;
+            break;
+        }
+        p2 = p2_new;
+    }
+
+    // Pressure along the line from the square law, ignoring elevation
+    let mut profile = vec![];
+    if p2 > 0.0 {
+        for i in 0..=PROFILE_POINTS {
+            let x = app.pipeline_length * i as f64 / PROFILE_POINTS as f64;
+            let p = (p1 * p1 - (p1 * p1 - p2 * p2) * i as f64 / PROFILE_POINTS as f64).sqrt();
+            profile.push(profile_point(app, eos.as_mut(), inlet.t, x, p));
+        }
+    }
+    PipelineResult {
+        inlet,
+        p2,
+        z_avg,
+        reynolds,
+        friction,
+        profile,
+    }
+}
+
+// Line starts at the inlet state when set, otherwise the current state
+pub fn pipeline(app: &App) -> PipelineResult {
+    let inlet = throttle::throttle_inlet(app);
+    let mu = transport::transport_properties(&app.gas_comp, app.model, inlet.t, inlet.d, inlet.mm, inlet.cp).viscosity;
+    let reynolds = 4.0 * app.flow_val / (PI * app.pipeline_d * mu);
+    match app.pipeline_eq {
+        Equation::Segmented => march(app, inlet, reynolds),
+        _ => flow_equation(app, inlet, reynolds),
+    }
+}
+
+pub fn set_param(app: &mut App, val: f64) {
+    match app.input_param {
+        1 => app.pipeline_length = units::set_distance(val, app.units.length),
+        2 => app.pipeline_d = units::set_length(val, app.units.length),
+        3 => app.pipeline_roughness = units::set_length(val, app.units.length),
+        4 => app.pipeline_dz = units::set_distance(val, app.units.length),
+        5 => app.pipeline_c_factor = val,
+        _ => {}
+    }
+}
+
+pub fn run_pipeline(app: &App) -> Vec<ListItem<'static>> {
+    let p_str = app.units.pressure.print_unit();
+    let length_str = app.units.length.print_unit();
+    let distance_str = app.units.length.print_distance_unit();
+    let speed_str = app.units.speed.print_unit();
+
+    let mut items = vec![
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "1-Length:", units::get_distance(app.pipeline_length, app.units.length), distance_str,
+            )
+        )
+            .fg(Color::LightYellow)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "2-Inside Diameter:", units::get_length(app.pipeline_d, app.units.length), length_str,
+            )
+        )
+            .fg(Color::LightYellow)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.6} {}",
+                "3-Roughness:", units::get_length(app.pipeline_roughness, app.units.length), length_str,
+            )
+        )
+            .fg(Color::LightYellow)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "4-Elevation Change:", units::get_distance(app.pipeline_dz, app.units.length), distance_str,
+            )
+        )
+            .fg(Color::LightYellow)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "5-Erosional C Factor:", app.pipeline_c_factor, "[]",
+            )
+        )
+            .fg(Color::LightYellow)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {}",
+                "E-Equation:", app.pipeline_eq.name(),
+            )
+        )
+            .fg(Color::LightYellow)
+            .bg(Color::Black),
+    ];
+
+    if app.flow_val <= 0.0 || app.pipeline_d <= 0.0 || app.pipeline_length <= 0.0 {
+        items.push(
+            ListItem::new("Enter the line geometry and a flow rate (F)")
+                .fg(Color::Red)
+                .bg(Color::Black)
+        );
+        return items
+    }
+
+    let result = pipeline(app);
+    if result.p2 <= 0.0 {
+        items.push(
+            ListItem::new("Flow exceeds the line capacity: outlet pressure falls to zero")
+                .fg(Color::Red)
+                .bg(Color::Black)
+        );
+        return items
+    }
+
+    let rows = [
+        ("Inlet Press:", units::get_pressure(result.inlet.p, app.units.pressure), p_str, Color::White),
+        ("Outlet Press:", units::get_pressure(result.p2, app.units.pressure), p_str, Color::LightCyan),
+        ("Pressure Drop:", units::get_pressure(result.inlet.p - result.p2, app.units.pressure), p_str, Color::LightCyan),
+        ("Average Z:", result.z_avg, "[]", Color::LightCyan),
+        ("Inlet Reynolds No:", result.reynolds, "[]", Color::LightCyan),
+        ("Darcy Friction Factor:", result.friction, "[]", Color::LightCyan),
+    ];
+    for (label, val, unit, color) in rows {
+        items.push(
+            ListItem::new(
+                format!("{:<24} {:.4} {}",
+                    label, val, unit,
+                )
+            )
+                .fg(color)
+                .bg(Color::Black)
+        );
+    }
+
+    items.push(
+        ListItem::new(
+            format!("{:<12} {:<14} {:<14} {}",
+                format!("Dist ({})", distance_str), format!("Press ({})", p_str), format!("Vel ({})", speed_str), "V/Ve",
+            )
+        )
+            .fg(Color::White)
+            .bg(Color::Black)
+    );
+    for point in result.profile.iter() {
+        let color = if point.erosional_ratio > 1.0 { Color::Red } else { Color::LightCyan };
+        items.push(
+            ListItem::new(
+                format!("{:<12.1} {:<14.4} {:<14.4} {:.4}",
+                    units::get_distance(point.x, app.units.length),
+                    units::get_pressure(point.p, app.units.pressure),
+                    units::get_speed(point.velocity, app.units.speed),
+                    point.erosional_ratio,
+                )
+            )
+                .fg(color)
+                .bg(Color::Black)
+        );
+    }
+    items
+}
@@ -0,0 +1,405 @@
+use aga8::gerg2008::Gerg2008;
+use ratatui::widgets::ListItem;
+use ratatui::
+    style::{
+        Color,
+        Stylize
+    };
+
+use crate::cubic::CRITICAL_DATA;
+use crate::eos::R;
+use crate::flash::{
+    self,
+    GasProps,
+};
+use crate::gas::components;
+use crate::units::{
+    self,
+    PrintUnit,
+};
+use crate::{
+    App,
+    recalculate,
+};
+
+// Normal boiling point pressure in kPa
+const NBP_PRESSURE: f64 = 101.325;
+const MAX_ITER: usize = 50;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum RefState {
+    Native,
+    Iir,
+    Ashrae,
+    Nbp,
+    User,
+}
+impl RefState {
+    pub fn name(&self) -> &'static str {
+        match self {
+            RefState::Native => "EOS Default",
+            RefState::Iir => "IIR",
+            RefState::Ashrae => "ASHRAE",
+            RefState::Nbp => "NBP",
+            RefState::User => "User Datum",
+        }
+    }
+
+    pub fn next(&self) -> RefState {
+        match self {
+            RefState::Native => RefState::Iir,
+            RefState::Iir => RefState::Ashrae,
+            RefState::Ashrae => RefState::Nbp,
+            RefState::Nbp => RefState::User,
+            RefState::User => RefState::Native,
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            RefState::Native => "Enthalpy and entropy on the equation of state datum",
+            RefState::Iir => "h = 200 kJ/kg, s = 1 kJ/(kg-K) for saturated liquid at 0 C",
+            RefState::Ashrae => "h = 0, s = 0 for saturated liquid at -40 C",
+            RefState::Nbp => "h = 0, s = 0 for saturated liquid at the normal boiling point",
+            RefState::User => "h = 0, s = 0 at the datum pressure and temperature",
+        }
+    }
+}
+
+// Offsets added to the equation of state enthalpy (J/mol) and entropy
+// (J/(mol-K)); differences between states are unchanged
+#[derive(Clone, Copy, Default)]
+pub struct Offset {
+    pub h: f64,
+    pub s: f64,
+}
+
+impl Offset {
+    pub fn apply(&self, props: &GasProps) -> GasProps {
+        GasProps {
+            u: props.u + self.h,
+            h: props.h + self.h,
+            s: props.s + self.s,
+            g: props.g + self.h - props.t * self.s,
+            ..*props
+        }
+    }
+}
+
+// Datum state of a convention: the state the values are fixed at and the
+// equation of state properties there
+pub struct Datum {
+    pub props: GasProps,
+    pub h: f64,
+    pub s: f64,
+}
+
+impl Datum {
+    pub fn offset(&self) -> Offset {
+        Offset {
+            h: self.h - self.props.h,
+            s: self.s - self.props.s,
+        }
+    }
+}
+
+fn gerg_props(gerg: &Gerg2008, p: f64) -> GasProps {
+    GasProps {
+        p,
+        t: gerg.t,
+        mm: gerg.mm,
+        d: gerg.d,
+        h: gerg.h,
+        s: gerg.s,
+        g: gerg.g,
+        ..Default::default()
+    }
+}
+
+// Vapor root at p and t
+fn vapor(gerg: &mut Gerg2008, p: f64, t: f64) -> GasProps {
+    gerg.p = p;
+    gerg.t = t;
+    gerg.d = 0.0;
+    let _ = gerg.density(0);
+    gerg.properties();
+    gerg_props(gerg, p)
+}
+
+// Liquid root at p and t by Newton iteration on density from the dense
+// side; the aga8 liquid search discards roots its phase checks reject. dc is
+// an estimate of the critical density (mol/l).
+fn liquid(gerg: &mut Gerg2008, p: f64, t: f64, dc: f64) -> Option<GasProps> {
+    gerg.t = t;
+    let mut d = 4.5 * dc;
+    for _ in 0..MAX_ITER {
+        gerg.d = d;
+        let p_calc = gerg.properties();
+        if gerg.dp_dd <= 0.0 {
+            return None
+        }
+        let step = (p_calc - p) / gerg.dp_dd;
+        d -= step;
+        if d < dc {
+            return None
+        }
+        if step.abs() < 1.0e-10 * d {
+            gerg.d = d;
+            gerg.properties();
+            return Some(gerg_props(gerg, p))
+        }
+    }
+    None
+}
+
+// Liquid and vapor roots at p and t when both exist
+fn phases(gerg: &mut Gerg2008, p: f64, t: f64, dc: f64) -> Option<(GasProps, GasProps)> {
+    let vapor = vapor(gerg, p, t);
+    let liquid = liquid(gerg, p, t, dc)?;
+    if liquid.d > vapor.d * 1.01 {
+        Some((liquid, vapor))
+    } else {
+        None
+    }
+}
+
+// Critical density estimate (mol/l) from Tc and Pc with Zc = 0.29
+fn critical_density(tc: f64, pc: f64) -> f64 {
+    pc / (0.29 * R * tc)
+}
+
+// Saturated liquid of a pure fluid at temperature t (K), from equal Gibbs
+// energy of the two roots
+fn saturated_liquid_t(gerg: &mut Gerg2008, t: f64, (tc, pc, omega): (f64, f64, f64)) -> Option<GasProps> {
+    if t >= tc {
+        return None
+    }
+    // Wilson vapor pressure estimate
+    let mut p = pc * (5.373 * (1.0 + omega) * (1.0 - tc / t)).exp();
+    for _ in 0..MAX_ITER {
+        let (liquid, vapor) = phases(gerg, p, t, critical_density(tc, pc))?;
+        let dg = liquid.g - vapor.g;
+        if dg.abs() < 1.0e-6 {
+            return Some(liquid)
+        }
+        let step = dg / (1.0 / liquid.d - 1.0 / vapor.d);
+        p = (p - step).clamp(p * 0.5, p * 2.0);
+    }
+    None
+}
+
+// Saturated liquid of a pure fluid at pressure p (kPa)
+fn saturated_liquid_p(gerg: &mut Gerg2008, p: f64, (tc, pc, omega): (f64, f64, f64)) -> Option<GasProps> {
+    if p >= pc {
+        return None
+    }
+    let mut t = tc / (1.0 - (p / pc).ln() / (5.373 * (1.0 + omega)));
+    for _ in 0..MAX_ITER {
+        let (liquid, vapor) = phases(gerg, p, t, critical_density(tc, pc))?;
+        let dg = liquid.g - vapor.g;
+        if dg.abs() < 1.0e-6 {
+            return Some(liquid)
+        }
+        let step = dg / (vapor.s - liquid.s);
+        t = (t - step).clamp(t * 0.9, t * 1.1);
+    }
+    None
+}
+
+// Datum of the selected convention. The saturation conventions need a pure
+// subcritical fluid and use GERG-2008, which shares its ideal gas datum with
+// the other models.
+pub fn datum(app: &App) -> Result<Datum, &'static str> {
+    match app.ref_state {
+        RefState::Native => return Ok(Datum {
+            props: GasProps::default(),
+            h: 0.0,
+            s: 0.0,
+        }),
+        RefState::User => return Ok(Datum {
+            props: flash::props_pt(&app.gas_comp, app.model, app.ref_p, app.ref_t),
+            h: 0.0,
+            s: 0.0,
+        }),
+        _ => {}
+    }
+    let pure: Vec<usize> = components(&app.gas_comp)
+        .iter()
+        .enumerate()
+        .filter(|(_, (_, x))| *x > 0.0)
+        .map(|(i, _)| i)
+        .collect();
+    if pure.len() != 1 {
+        return Err("Saturation conventions need a pure fluid")
+    }
+    let critical = CRITICAL_DATA[pure[0]];
+    let mut gerg = Gerg2008::new();
+    let _ = gerg.set_composition(&app.gas_comp);
+    let liquid = match app.ref_state {
+        RefState::Iir => saturated_liquid_t(&mut gerg, 273.15, critical),
+        RefState::Ashrae => saturated_liquid_t(&mut gerg, 233.15, critical),
+        _ => saturated_liquid_p(&mut gerg, NBP_PRESSURE, critical),
+    };
+    let liquid = liquid.ok_or("No saturated liquid at the datum (supercritical or not converged)")?;
+    let (h, s) = if app.ref_state == RefState::Iir {
+        // 200 kJ/kg and 1 kJ/(kg-K) converted to a molar basis
+        (200.0 * liquid.mm, liquid.mm)
+    } else {
+        (0.0, 0.0)
+    };
+    Ok(Datum {
+        props: liquid,
+        h,
+        s,
+    })
+}
+
+// Offset for the selected convention, none when it cannot be applied
+pub fn offset(app: &App) -> Offset {
+    datum(app).map(|datum| datum.offset()).unwrap_or_default()
+}
+
+pub fn set_param(app: &mut App, val: f64) {
+    match app.input_param {
+        1 => app.ref_p = units::set_pressure(val, app.units.pressure),
+        2 => app.ref_t = units::set_temperature(val, app.units.temp),
+        _ => {}
+    }
+    recalculate(app);
+}
+
+pub fn run_refstate(app: &App) -> Vec<ListItem<'static>> {
+    let p_str = app.units.pressure.print_unit();
+    let t_str = app.units.temp.print_unit();
+    let energy_str = app.units.energy.print_unit();
+    let entropy_str = app.units.entropy.print_unit();
+    let mut items = vec![
+        ListItem::new(
+            format!("{:<24} {}",
+                "Convention:", app.ref_state.name(),
+            )
+        )
+            .fg(Color::LightYellow)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "1-Datum Pressure:", units::get_pressure(app.ref_p, app.units.pressure), p_str,
+            )
+        )
+            .fg(Color::LightYellow)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "2-Datum Temperature:", units::get_temperature(app.ref_t, app.units.temp), t_str,
+            )
+        )
+            .fg(Color::LightYellow)
+            .bg(Color::Black),
+
+        ListItem::new(app.ref_state.description())
+            .fg(Color::White)
+            .bg(Color::Black),
+    ];
+
+    if app.ref_state == RefState::Native {
+        return items
+    }
+
+    let datum = match datum(app) {
+        Ok(datum) => datum,
+        Err(msg) => {
+            items.push(
+                ListItem::new(format!("{}; values are on the EOS datum", msg))
+                    .fg(Color::Red)
+                    .bg(Color::Black)
+            );
+            return items
+        }
+    };
+    let mm = datum.props.mm;
+    let offset = datum.offset();
+    let state = offset.apply(&app.cur_state);
+    let rows = [
+        ("Datum Pressure:", units::get_pressure(datum.props.p, app.units.pressure), p_str, Color::White),
+        ("Datum Temperature:", units::get_temperature(datum.props.t, app.units.temp), t_str, Color::White),
+        ("Enthalpy Offset:", units::get_energy(offset.h, app.units.energy, mm), energy_str, Color::LightCyan),
+        ("Entropy Offset:", units::get_entropy(offset.s, app.units.entropy, mm), entropy_str, Color::LightCyan),
+        ("Current Enthalpy:", units::get_energy(state.h, app.units.energy, state.mm), energy_str, Color::LightCyan),
+        ("Current Entropy:", units::get_entropy(state.s, app.units.entropy, state.mm), entropy_str, Color::LightCyan),
+    ];
+    for (label, val, unit, color) in rows {
+        items.push(
+            ListItem::new(
+                format!("{:<24} {:.4} {}",
+                    label, val, unit,
+                )
+            )
+                .fg(color)
+                .bg(Color::Black)
+        );
+    }
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aga8::composition::Composition;
+
+    fn datum_of(gas_comp: Composition, ref_state: RefState) -> Datum {
+        let app = App {
+            gas_comp,
+            ref_state,
+            ..Default::default()
+        };
+        datum(&app).unwrap_or_else(|msg| panic!("{}", msg))
+    }
+
+    // Normal boiling points (NIST): propane 231.04 K, nitrogen 77.355 K
+    #[test]
+    fn normal_boiling_points() {
+        let propane = Composition { propane: 1.0, ..Default::default() };
+        let nitrogen = Composition { nitrogen: 1.0, ..Default::default() };
+        assert!((datum_of(propane, RefState::Nbp).props.t - 231.04).abs() < 0.1);
+        assert!((datum_of(nitrogen, RefState::Nbp).props.t - 77.355).abs() < 0.05);
+    }
+
+    // Vapor pressures at 0 C (NIST): carbon dioxide 3485 kPa, propane
+    // 474.5 kPa, water 0.6112 kPa
+    #[test]
+    fn vapor_pressures_at_0_c() {
+        let cases = [
+            (Composition { carbon_dioxide: 1.0, ..Default::default() }, 3485.0),
+            (Composition { propane: 1.0, ..Default::default() }, 474.5),
+            (Composition { water: 1.0, ..Default::default() }, 0.6112),
+        ];
+        for (gas_comp, p) in cases {
+            let datum = datum_of(gas_comp, RefState::Iir);
+            assert!((datum.props.p / p - 1.0).abs() < 0.005, "{} {}", p, datum.props.p);
+        }
+    }
+
+    // The offset puts the saturated liquid at 0 C on 200 kJ/kg and
+    // 1 kJ/(kg-K)
+    #[test]
+    fn iir_datum_values() {
+        let datum = datum_of(Composition { propane: 1.0, ..Default::default() }, RefState::Iir);
+        let liquid = datum.offset().apply(&datum.props);
+        assert!((liquid.h / liquid.mm - 200.0).abs() < 1.0e-9);
+        assert!((liquid.s / liquid.mm - 1.0).abs() < 1.0e-12);
+    }
+
+    #[test]
+    fn supercritical_and_mixture_datum() {
+        let mut app = App {
+            gas_comp: Composition { methane: 1.0, ..Default::default() },
+            ref_state: RefState::Iir,
+            ..Default::default()
+        };
+        assert!(datum(&app).is_err());
+        app.gas_comp = crate::gas::get_gas_comp(crate::gas::Gas::Air);
+        assert!(datum(&app).is_err());
+    }
+}
@@ -0,0 +1,292 @@
+use ratatui::widgets::ListItem;
+use ratatui::
+    style::{
+        Color,
+        Stylize
+    };
+
+use crate::flash::{
+    self,
+    GasProps,
+};
+use crate::nozzle;
+use crate::units::{
+    self,
+    Length,
+    PrintUnit,
+};
+use crate::App;
+
+const ATM: f64 = 101.325;
+const MM2_PER_IN2: f64 = 645.16;
+
+// API 526 letter orifices and effective areas (in^2)
+const API_526_ORIFICES: [(&str, f64); 14] = [
+    ("D", 0.110),
+    ("E", 0.196),
+    ("F", 0.307),
+    ("G", 0.503),
+    ("H", 0.785),
+    ("J", 1.287),
+    ("K", 1.838),
+    ("L", 2.853),
+    ("M", 3.60),
+    ("N", 4.34),
+    ("P", 6.38),
+    ("Q", 11.05),
+    ("R", 16.0),
+    ("T", 26.0),
+];
+
+pub struct ReliefResult {
+    pub relieving: GasProps,
+    pub k: f64,
+    pub p_critical: f64,
+    pub critical: bool,
+    // Required effective areas in mm^2
+    pub area_ideal: f64,
+    pub area_real: f64,
+}
+
+// Relieving temperature defaults to the current state temperature when unset
+pub fn relieving_temp(app: &App) -> f64 {
+    if app.relief_t > 0.0 {
+        app.relief_t
+    } else {
+        app.cur_state.t
+    }
+}
+
+// Smallest API 526 orifice with at least the required area (mm^2)
+pub fn select_orifice(area: f64) -> Option<(&'static str, f64)> {
+    API_526_ORIFICES
+        .iter()
+        .find(|(_, a)| a * MM2_PER_IN2 >= area)
+        .map(|(letter, a)| (*letter, a * MM2_PER_IN2))
+}
+
+// API 520 Part I ideal gas formulas in SI units: required area (mm^2) for a
+// flow (kg/h) at the relieving state (kPa abs, K) with its cp/cv ratio k
+pub fn ideal_area(w_kg_h: f64, relieving: &GasProps, k: f64, kd: f64, back_p: f64) -> f64 {
+    let (p1, t1) = (relieving.p, relieving.t);
+    let p_critical = p1 * (2.0 / (k + 1.0)).powf(k / (k - 1.0));
+    if back_p <= p_critical {
+        let c = 0.03948 * (k * (2.0 / (k + 1.0)).powf((k + 1.0) / (k - 1.0))).sqrt();
+        w_kg_h / (c * kd * p1) * (t1 * relieving.z / relieving.mm).sqrt()
+    } else {
+        let r = back_p / p1;
+        let f2 = (k / (k - 1.0) * r.powf(2.0 / k) * (1.0 - r.powf((k - 1.0) / k)) / (1.0 - r)).sqrt();
+        17.9 * w_kg_h / (f2 * kd) * (t1 * relieving.z / (relieving.mm * p1 * (p1 - back_p))).sqrt()
+    }
+}
+
+pub fn size_relief(app: &App) -> ReliefResult {
+    let p1 = app.relief_set_p * (1.0 + app.relief_overpressure / 100.0) + ATM;
+    let t1 = relieving_temp(app);
+    let relieving = flash::props_pt(&app.gas_comp, app.model, p1, t1);
+    let k = relieving.cp / relieving.cv;
+    let w_kg_h = app.flow_val * 3600.0;
+    let p_critical = p1 * (2.0 / (k + 1.0)).powf(k / (k - 1.0));
+    let critical = app.relief_back_p <= p_critical;
+    let area_ideal = ideal_area(w_kg_h, &relieving, k, app.relief_kd, app.relief_back_p);
+
+    // Real gas isentropic nozzle: mass flux at the sonic throat, or at the back
+    // pressure when the flow is subcritical
+    let throat = nozzle::throat_state(app, &relieving);
+    let outlet = if throat.p < app.relief_back_p {
+        flash::props_ps(&app.gas_comp, app.model, app.relief_back_p, relieving.s, throat.t)
+    } else {
+        throat
+    };
+    let velocity = (2.0 * (relieving.h - outlet.h) / outlet.mm * 1000.0).max(0.0).sqrt();
+    let mass_flux = outlet.d * outlet.mm * velocity;
+    let area_real = app.flow_val / (app.relief_kd * mass_flux) * 1.0e6;
+
+    ReliefResult {
+        relieving,
+        k,
+        p_critical,
+        critical,
+        area_ideal,
+        area_real,
+    }
+}
+
+// Area in mm^2 for metric length units, in^2 otherwise
+fn get_area(area_mm2: f64, unit: Length) -> (f64, &'static str) {
+    match unit {
+        Length::inch | Length::ft => (area_mm2 / MM2_PER_IN2, "in^2"),
+        _ => (area_mm2, "mm^2"),
+    }
+}
+
+pub fn set_param(app: &mut App, val: f64) {
+    match app.input_param {
+        1 => app.relief_set_p = units::set_pressure(val, app.units.pressure),
+        2 => app.relief_overpressure = val,
+        3 => app.relief_t = units::set_temperature(val, app.units.temp),
+        4 => app.relief_back_p = units::set_pressure(val, app.units.pressure),
+        5 => app.relief_kd = val,
+        _ => {}
+    }
+}
+
+pub fn run_relief(app: &App) -> Vec<ListItem<'static>> {
+    let p_str = app.units.pressure.print_unit();
+    let t_str = app.units.temp.print_unit();
+    let flow_str = app.units.flow.print_unit();
+    let result = size_relief(app);
+
+    let mut items = vec![
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "1-Set Pressure:", units::get_pressure(app.relief_set_p, app.units.pressure), app.units.pressure.print_gauge_unit(),
+            )
+        )
+            .fg(Color::LightYellow)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "2-Overpressure:", app.relief_overpressure, "%",
+            )
+        )
+            .fg(Color::LightYellow)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "3-Relieving Temp:", units::get_temperature(relieving_temp(app), app.units.temp), t_str,
+            )
+        )
+            .fg(Color::LightYellow)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "4-Back Pressure:", units::get_pressure(app.relief_back_p, app.units.pressure), p_str,
+            )
+        )
+            .fg(Color::LightYellow)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "5-Discharge Coeff Kd:", app.relief_kd, "[]",
+            )
+        )
+            .fg(Color::LightYellow)
+            .bg(Color::Black),
+    ];
+
+    if app.relief_set_p <= 0.0 || app.flow_val <= 0.0 {
+        items.push(
+            ListItem::new("Enter a set pressure (1) and a relieving flow (F)")
+                .fg(Color::Red)
+                .bg(Color::Black)
+        );
+        return items
+    }
+
+    let (area_ideal, area_str) = get_area(result.area_ideal, app.units.length);
+    let (area_real, _) = get_area(result.area_real, app.units.length);
+    let flow_regime = if result.critical { "Critical" } else { "Subcritical" };
+    let rows = [
+        ("Relieving Flow:", units::get_flow(app.flow_val, app.units.flow, &app.gas_comp, app.stp_60_F, app.model), flow_str, Color::White),
+        ("Relieving Press:", units::get_pressure(result.relieving.p, app.units.pressure), p_str, Color::White),
+        ("Relieving k (cp/cv):", result.k, "[]", Color::White),
+        ("Relieving Z:", result.relieving.z, "[]", Color::White),
+        ("Molar Mass:", result.relieving.mm, "g/mol", Color::White),
+        ("Critical Flow Press:", units::get_pressure(result.p_critical, app.units.pressure), p_str, Color::LightCyan),
+        ("Area (API Formula):", area_ideal, area_str, Color::LightCyan),
+        ("Area (Real Gas Nozzle):", area_real, area_str, Color::LightCyan),
+    ];
+    for (label, val, unit, color) in rows {
+        items.push(
+            ListItem::new(
+                format!("{:<24} {:.4} {}",
+                    label, val, unit,
+                )
+            )
+                .fg(color)
+                .bg(Color::Black)
+        );
+    }
+    items.push(
+        ListItem::new(
+            format!("{:<24} {}",
+                "Flow Regime:", flow_regime,
+            )
+        )
+            .fg(Color::LightCyan)
+            .bg(Color::Black)
+    );
+
+    for (label, area) in [("API 526 Orifice:", result.area_ideal), ("API 526 (Real Gas):", result.area_real)] {
+        let item = match select_orifice(area) {
+            Some((letter, orifice_area)) => {
+                let (orifice_area, _) = get_area(orifice_area, app.units.length);
+                ListItem::new(
+                    format!("{:<24} {}  {:.4} {}",
+                        label, letter, orifice_area, area_str,
+                    )
+                )
+                    .fg(Color::LightGreen)
+                    .bg(Color::Black)
+            },
+            None => {
+                ListItem::new(
+                    format!("{:<24} {}",
+                        label, "Larger than T, use multiple valves",
+                    )
+                )
+                    .fg(Color::Red)
+                    .bg(Color::Black)
+            },
+        };
+        items.push(item);
+    }
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // API 520 Part I sizing example: 24270 kg/h of a hydrocarbon vapor with
+    // M = 51, Z = 0.90 and k = 1.11 relieving at 670 kPa abs and 348 K,
+    // Kd = 0.975, needs 3698 mm^2 and a P orifice
+    #[test]
+    fn api_520_critical_example() {
+        let relieving = GasProps {
+            p: 670.0,
+            t: 348.0,
+            z: 0.90,
+            mm: 51.0,
+            ..Default::default()
+        };
+        let area = ideal_area(24270.0, &relieving, 1.11, 0.975, ATM);
+        assert!((area / 3698.0 - 1.0).abs() < 1.0e-3, "area {}", area);
+        assert_eq!(select_orifice(area).map(|(letter, _)| letter), Some("P"));
+    }
+
+    // Subcritical flow needs more area than critical flow at the same
+    // relieving state, and the two formulas meet at the critical pressure
+    #[test]
+    fn subcritical_area() {
+        let relieving = GasProps {
+            p: 670.0,
+            t: 348.0,
+            z: 0.90,
+            mm: 51.0,
+            ..Default::default()
+        };
+        let k: f64 = 1.11;
+        let p_critical = 670.0 * (2.0 / (k + 1.0)).powf(k / (k - 1.0));
+        let critical = ideal_area(24270.0, &relieving, k, 0.975, p_critical);
+        let limit = ideal_area(24270.0, &relieving, k, 0.975, p_critical + 1.0e-6);
+        let subcritical = ideal_area(24270.0, &relieving, k, 0.975, 532.0);
+        assert!((limit / critical - 1.0).abs() < 2.0e-3, "{} {}", limit, critical);
+        assert!(subcritical > critical);
+    }
+}
@@ -0,0 +1,163 @@
+use ratatui::widgets::ListItem;
+use ratatui::
+    style::{
+        Color,
+        Stylize
+    };
+
+use crate::blowdown;
+use crate::flash;
+use crate::linepack::{
+    energy_content,
+    std_volume,
+};
+use crate::units::{
+    self,
+    PrintUnit,
+};
+use crate::App;
+
+// Gas constant in J/(mol-K)
+const R: f64 = 8.314462618;
+
+pub struct Inventory {
+    // Storage pressure (kPa) and temperature (K) from the current state
+    pub p: f64,
+    pub t: f64,
+    // Stored gas in kg and mol
+    pub mass: f64,
+    pub moles: f64,
+    // Stored mass if the gas were ideal, in kg
+    pub mass_ideal: f64,
+    // Usable mass between the maximum and minimum pressures, in kg
+    pub usable: f64,
+    pub z: f64,
+    pub mm: f64,
+}
+
+// Inventory of all storage units at the current state
+pub fn inventory(app: &App) -> Inventory {
+    let s = &app.cur_state;
+    let (p, t, d, mm, z) = (s.p, s.t, s.d, s.mm, s.z);
+    let volume = app.storage_volume * app.storage_count;
+    let max = flash::props_pt(&app.gas_comp, app.model, app.storage_p_max, t);
+    let min = flash::props_pt(&app.gas_comp, app.model, app.storage_p_min, t);
+    Inventory {
+        p,
+        t,
+        mass: d * mm * volume,
+        moles: d * volume * 1000.0,
+        mass_ideal: p / (R * t) * mm * volume,
+        usable: (max.d - min.d) * mm * volume,
+        z,
+        mm,
+    }
+}
+
+// Standard volume (m^3) and energy (GJ) in units sized for single vessels
+fn small_units(app: &App, std_volume: f64, energy: f64) -> [(f64, &'static str); 2] {
+    if app.units.flow.is_imperial() {
+        [(std_volume * 35.3147, "scf"), (energy * 947817.0, "BTU")]
+    } else {
+        [(std_volume, "Sm^3"), (energy * 1000.0, "MJ")]
+    }
+}
+
+pub fn set_param(app: &mut App, val: f64) {
+    match app.input_param {
+        1 => app.storage_volume = blowdown::set_volume(app, val),
+        2 => app.storage_count = val.round().max(1.0),
+        3 => app.storage_p_max = units::set_pressure(val, app.units.pressure),
+        4 => app.storage_p_min = units::set_pressure(val, app.units.pressure),
+        5 => app.storage_target = if app.units.flow.is_imperial() { val / 2.20462 } else { val },
+        _ => {}
+    }
+}
+
+pub fn run_storage(app: &App) -> Vec<ListItem<'static>> {
+    let p_str = app.units.pressure.print_unit();
+    let t_str = app.units.temp.print_unit();
+    let (volume, volume_str) = blowdown::get_volume(app, app.storage_volume);
+    let (target, mass_str) = blowdown::get_mass(app, app.storage_target);
+
+    let inputs = [
+        ("1-Volume per Unit:", volume, volume_str),
+        ("2-Number of Units:", app.storage_count, "[]"),
+        ("3-Max Pressure:", units::get_pressure(app.storage_p_max, app.units.pressure), p_str),
+        ("4-Min Pressure:", units::get_pressure(app.storage_p_min, app.units.pressure), p_str),
+        ("5-Target Quantity:", target, mass_str),
+    ];
+    let mut items: Vec<ListItem<'static>> = inputs
+        .iter()
+        .map(|(label, val, unit)| {
+            ListItem::new(
+                format!("{:<24} {:.4} {}",
+                    label, val, unit,
+                )
+            )
+                .fg(Color::LightYellow)
+                .bg(Color::Black)
+        })
+        .collect();
+
+    if app.storage_volume <= 0.0 {
+        items.push(
+            ListItem::new("Enter the storage volume (1)")
+                .fg(Color::Red)
+                .bg(Color::Black)
+        );
+        return items
+    }
+
+    let inv = inventory(app);
+    let stored = small_units(app, std_volume(app, inv.mass), energy_content(app, inv.mass, inv.mm));
+    let usable = small_units(app, std_volume(app, inv.usable), energy_content(app, inv.usable, inv.mm));
+    let ideal_error = (inv.mass_ideal - inv.mass) / inv.mass * 100.0;
+    let (ideal_mass, _) = blowdown::get_mass(app, inv.mass_ideal);
+    let (stored_mass, _) = blowdown::get_mass(app, inv.mass);
+
+    let rows = [
+        ("Storage Pressure:", units::get_pressure(inv.p, app.units.pressure), p_str, Color::White),
+        ("Storage Temperature:", units::get_temperature(inv.t, app.units.temp), t_str, Color::White),
+        ("Compressibility Z:", inv.z, "[]", Color::White),
+        ("Stored Mass:", stored_mass, mass_str, Color::LightCyan),
+        ("Stored Moles:", inv.moles, "mol", Color::LightCyan),
+        ("Stored Std Volume:", stored[0].0, stored[0].1, Color::LightCyan),
+        ("Stored Energy:", stored[1].0, stored[1].1, Color::LightCyan),
+        ("Ideal Gas Mass:", ideal_mass, mass_str, Color::White),
+        ("Ideal Gas Error:", ideal_error, "%", Color::White),
+        ("Usable Mass:", blowdown::get_mass(app, inv.usable).0, mass_str, Color::LightCyan),
+        ("Usable Std Volume:", usable[0].0, usable[0].1, Color::LightCyan),
+        ("Usable Energy:", usable[1].0, usable[1].1, Color::LightCyan),
+    ];
+    for (label, val, unit, color) in rows {
+        items.push(
+            ListItem::new(
+                format!("{:<24} {:.4} {}",
+                    label, val, unit,
+                )
+            )
+                .fg(color)
+                .bg(Color::Black)
+        );
+    }
+
+    if app.storage_target > 0.0 {
+        let usable_per_unit = inv.usable / app.storage_count;
+        let item = if usable_per_unit > 0.0 {
+            ListItem::new(
+                format!("{:<24} {:.0} {}",
+                    "Units for Target:", (app.storage_target / usable_per_unit).ceil(), "[]",
+                )
+            )
+                .fg(Color::LightGreen)
+                .bg(Color::Black)
+        } else {
+            ListItem::new("Max pressure must exceed min pressure for usable inventory")
+                .fg(Color::Red)
+                .bg(Color::Black)
+        };
+        items.push(item);
+    }
+    items
+}
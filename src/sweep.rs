@@ -0,0 +1,482 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{
+    SystemTime,
+    UNIX_EPOCH,
+};
+
+use aga8::composition::Composition;
+use ratatui::layout::Constraint;
+use ratatui::widgets::{
+    ListItem,
+    Row,
+    Table,
+};
+use ratatui::
+    style::{
+        Color,
+        Style,
+        Stylize
+    };
+
+use crate::eos::{
+    self,
+    new_eos,
+    GasProps,
+};
+use crate::gas::{
+    components,
+    component_mut,
+    mix_composition,
+};
+use crate::hydrate;
+use crate::transport;
+use crate::units::{
+    self,
+    PrintUnit,
+};
+use crate::App;
+
+// Upper limit on the table length
+const MAX_POINTS: usize = 500;
+// Stem of the file the table is exported to, in the working directory
+const FILE_NAME: &str = "gas_calc_sweep";
+// Minimum table column width
+const COLUMN_WIDTH: usize = 12;
+
+// Output properties in the order of the state panels
+pub const PROPERTIES: [&str; 23] = [
+    "Pressure",
+    "Temperature",
+    "Density",
+    "Molar Mass",
+    "Internal Energy",
+    "Enthalpy",
+    "Entropy",
+    "Cp",
+    "Cv",
+    "Cp/Cv (k)",
+    "Z",
+    "Speed of Sound",
+    "JT Coeff",
+    "Isothermal Comp",
+    "Vol Expansivity",
+    "dP/dT (const D)",
+    "dP/dD (const T)",
+    "2nd Virial Coeff",
+    "Viscosity",
+    "Kin. Viscosity",
+    "Conductivity",
+    "Prandtl No",
+    "Hydrate Temp",
+];
+
+// Density, enthalpy, entropy, Z and speed of sound
+pub const DEFAULT_PROPERTIES: [bool; 23] = [
+    false, false, true, false, false, true, true, false, false, false, true, true,
+    false, false, false, false, false, false, false, false, false, false, false,
+];
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Variable {
+    Pressure,
+    Temperature,
+    // Mole fraction of a component, by its index in gas::components
+    Component(usize),
+}
+impl Variable {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Variable::Pressure => "Pressure",
+            Variable::Temperature => "Temperature",
+            Variable::Component(index) => components(&Composition::default())[*index].0,
+        }
+    }
+
+    pub fn next(&self, gas_comp: &Composition) -> Variable {
+        match self {
+            Variable::Pressure => Variable::Temperature,
+            Variable::Temperature => {
+                // Start on the main component of the active gas
+                let index = components(gas_comp)
+                    .iter()
+                    .enumerate()
+                    .max_by(|a, b| a.1.1.total_cmp(&b.1.1))
+                    .map(|(i, _)| i)
+                    .unwrap_or(0);
+                Variable::Component(index)
+            },
+            Variable::Component(_) => Variable::Pressure,
+        }
+    }
+
+    // Default start, stop and step in kPa, K or mole fraction
+    pub fn default_range(&self) -> (f64, f64, f64) {
+        match self {
+            Variable::Pressure => (100.0, 10000.0, 500.0),
+            Variable::Temperature => (250.0, 350.0, 10.0),
+            Variable::Component(_) => (0.0, 1.0, 0.1),
+        }
+    }
+}
+
+pub struct Sweep {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<f64>>,
+}
+
+// Active composition with component index at mole fraction x, the other
+// components scaled to keep their ratios
+fn swept_composition(gas_comp: &Composition, index: usize, x: f64) -> Option<Composition> {
+    let rest = 1.0 - components(gas_comp)[index].1;
+    if rest <= 0.0 {
+        return None
+    }
+    let mut comp = mix_composition(&[(gas_comp, 1.0)]);
+    for j in 0..components(gas_comp).len() {
+        *component_mut(&mut comp, j) *= (1.0 - x) / rest;
+    }
+    *component_mut(&mut comp, index) = x;
+    Some(comp)
+}
+
+fn property_units(app: &App) -> [String; 23] {
+    let p_str = app.units.pressure.print_unit();
+    let t_str = app.units.temp.print_unit();
+    let d_str = app.units.density.print_unit();
+    let energy_str = app.units.energy.print_unit();
+    let entropy_str = app.units.entropy.print_unit();
+    [
+        p_str.to_string(),
+        t_str.to_string(),
+        d_str.to_string(),
+        "g/mol".to_string(),
+        energy_str.to_string(),
+        energy_str.to_string(),
+        entropy_str.to_string(),
+        entropy_str.to_string(),
+        entropy_str.to_string(),
+        "[]".to_string(),
+        "[]".to_string(),
+        app.units.speed.print_unit().to_string(),
+        app.units.jt_coeff.print_unit().to_string(),
+        format!("1/{}", p_str),
+        format!("1/{}", t_str),
+        format!("{}/{}", p_str, t_str),
+        format!("{}/({})", p_str, d_str),
+        app.units.density.print_volume_unit().to_string(),
+        app.units.viscosity.print_unit().to_string(),
+        app.units.viscosity.print_kinematic_unit().to_string(),
+        app.units.conductivity.print_unit().to_string(),
+        "[]".to_string(),
+        t_str.to_string(),
+    ]
+}
+
+// Property values in display units, with the enthalpy and entropy on the
+// selected reference state
+fn property_values(app: &App, gas_comp: &Composition, state: &GasProps) -> [f64; 23] {
+    let props = app.ref_offset.apply(state);
+    let mm = props.mm;
+    let tp = transport::transport_properties(gas_comp, app.model, props.t, props.d, mm, props.cp);
+    let b = eos::second_virial(gas_comp, app.model, props.t);
+    // Not a number where the hydrate correlation does not apply
+    let t_hyd = hydrate::hydrate_temp_of(app, gas_comp, mm, props.p)
+        .map_or(f64::NAN, |t_hyd| units::get_temperature(t_hyd, app.units.temp));
+    [
+        units::get_pressure(props.p, app.units.pressure),
+        units::get_temperature(props.t, app.units.temp),
+        units::get_density(props.d, app.units.density, mm),
+        mm,
+        units::get_energy(props.u, app.units.energy, mm),
+        units::get_energy(props.h, app.units.energy, mm),
+        units::get_entropy(props.s, app.units.entropy, mm),
+        units::get_entropy(props.cp, app.units.entropy, mm),
+        units::get_entropy(props.cv, app.units.entropy, mm),
+        props.kappa,
+        props.z,
+        units::get_speed(props.w, app.units.speed),
+        units::get_jt_coeff(props.jt, app.units.jt_coeff),
+        units::get_compressibility(props.isothermal_compressibility(), app.units.pressure),
+        units::get_expansivity(props.volume_expansivity(), app.units.temp),
+        units::get_dp_dt(props.dp_dt, app.units.pressure, app.units.temp),
+        units::get_dp_dd(props.dp_dd, app.units.pressure, app.units.density, mm),
+        units::get_specific_volume(b, app.units.density, mm),
+        units::get_viscosity(tp.viscosity, app.units.viscosity),
+        units::get_kinematic_viscosity(tp.kinematic_viscosity, app.units.viscosity),
+        units::get_conductivity(tp.conductivity, app.units.conductivity),
+        tp.prandtl,
+        t_hyd,
+    ]
+}
+
+// Swept variable in display units
+fn get_variable(app: &App, val: f64) -> f64 {
+    match app.sweep_variable {
+        Variable::Pressure => units::get_pressure(val, app.units.pressure),
+        Variable::Temperature => units::get_temperature(val, app.units.temp),
+        Variable::Component(_) => val,
+    }
+}
+
+fn variable_unit(app: &App) -> &'static str {
+    match app.sweep_variable {
+        Variable::Pressure => app.units.pressure.print_unit(),
+        Variable::Temperature => app.units.temp.print_unit(),
+        Variable::Component(_) => "mol frac",
+    }
+}
+
+// Values from start to stop in steps, including stop when the step lands on
+// it; either end may be the larger
+pub fn range(start: f64, stop: f64, step: f64, max_points: usize) -> Result<Vec<f64>, &'static str> {
+    if step <= 0.0 {
+        return Err("Step must be greater than zero")
+    }
+    let count = ((stop - start).abs() / step + 1.0e-9).floor() as usize + 1;
+    if count > max_points {
+        return Err("Too many points, increase the step")
+    }
+    let sign = if stop < start { -1.0 } else { 1.0 };
+    Ok((0..count).map(|i| start + sign * step * i as f64).collect())
+}
+
+// Values of the swept variable
+pub fn points(app: &App) -> Result<Vec<f64>, &'static str> {
+    let (start, stop) = (app.sweep_start, app.sweep_stop);
+    if let Variable::Component(_) = app.sweep_variable {
+        if !(0.0..=1.0).contains(&start) || !(0.0..=1.0).contains(&stop) {
+            return Err("Mole fractions must be between 0 and 1")
+        }
+    } else if start <= 0.0 || stop <= 0.0 {
+        return Err("Pressure and temperature must be greater than zero")
+    }
+    range(start, stop, app.sweep_step, MAX_POINTS)
+}
+
+// Property table over the swept variable, at the current state pressure and
+// temperature for the variables not swept
+pub fn sweep(app: &App) -> Result<Sweep, &'static str> {
+    let selected: Vec<usize> = (0..PROPERTIES.len())
+        .filter(|i| app.sweep_props[*i])
+        .collect();
+    if selected.is_empty() {
+        return Err("Select at least one output property")
+    }
+    let property_units = property_units(app);
+    let mut headers = vec![format!("{} ({})", app.sweep_variable.name(), variable_unit(app))];
+    headers.extend(selected.iter().map(|i| format!("{} ({})", PROPERTIES[*i], property_units[*i])));
+
+    let (p, t) = (app.cur_state.p, app.cur_state.t);
+    let mut eos = new_eos(app.model, &app.gas_comp);
+    let mut rows = vec![];
+    for val in points(app)? {
+        let (gas_comp, state) = match app.sweep_variable {
+            Variable::Pressure => (None, eos.props_pt(val, t)),
+            Variable::Temperature => (None, eos.props_pt(p, val)),
+            Variable::Component(index) => {
+                let comp = swept_composition(&app.gas_comp, index, val)
+                    .ok_or("Cannot vary the only component of the gas")?;
+                eos.set_composition(&comp);
+                let state = eos.props_pt(p, t);
+                (Some(comp), state)
+            },
+        };
+        let values = property_values(app, gas_comp.as_ref().unwrap_or(&app.gas_comp), &state);
+        let mut row = vec![get_variable(app, val)];
+        row.extend(selected.iter().map(|i| values[*i]));
+        rows.push(row);
+    }
+    Ok(Sweep {
+        headers,
+        rows,
+    })
+}
+
+pub fn csv_field(text: &str) -> String {
+    if text.contains(',') || text.contains('"') {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+// Writes the text to a new file in the working directory, named with the
+// export time so an earlier export is never overwritten, and returns the
+// absolute path
+pub fn write_export(stem: &str, extension: &str, text: &str) -> Result<PathBuf, String> {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|err| err.to_string())?
+        .as_secs();
+    let path = std::env::current_dir()
+        .map_err(|err| err.to_string())?
+        .join(format!("{}_{}.{}", stem, secs, extension));
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&path)
+        .and_then(|mut file| file.write_all(text.as_bytes()))
+        .map_err(|err| err.to_string())?;
+    Ok(path)
+}
+
+pub fn export_csv(app: &mut App) {
+    let result = sweep(app).map_err(|msg| msg.to_string()).and_then(|sweep| {
+        let mut text = sweep.headers
+            .iter()
+            .map(|header| csv_field(header))
+            .collect::<Vec<String>>()
+            .join(",");
+        text.push('\n');
+        for row in &sweep.rows {
+            let line = row
+                .iter()
+                .map(|val| if val.is_nan() { String::new() } else { format!("{}", val) })
+                .collect::<Vec<String>>()
+                .join(",");
+            text.push_str(&line);
+            text.push('\n');
+        }
+        write_export(FILE_NAME, "csv", &text).map(|path| (sweep.rows.len(), path))
+    });
+    app.sweep_message = match result {
+        Ok((count, path)) => format!("Exported {} rows to {}", count, path.display()),
+        Err(msg) => format!("Export failed: {}", msg),
+    };
+}
+
+pub fn set_variable(app: &mut App, variable: Variable) {
+    app.sweep_variable = variable;
+    (app.sweep_start, app.sweep_stop, app.sweep_step) = variable.default_range();
+    app.sweep_scroll = 0;
+    app.sweep_message.clear();
+}
+
+// Next component to sweep when a component is selected
+pub fn next_component(app: &mut App) {
+    if let Variable::Component(index) = app.sweep_variable {
+        app.sweep_variable = Variable::Component((index + 1) % components(&app.gas_comp).len());
+        app.sweep_scroll = 0;
+        app.sweep_message.clear();
+    }
+}
+
+pub fn toggle_property(app: &mut App) {
+    app.sweep_props[app.sweep_cursor] = !app.sweep_props[app.sweep_cursor];
+    app.sweep_message.clear();
+}
+
+pub fn set_param(app: &mut App, val: f64) {
+    let to_internal = |val: f64| match app.sweep_variable {
+        Variable::Pressure => units::set_pressure(val, app.units.pressure),
+        Variable::Temperature => units::set_temperature(val, app.units.temp),
+        Variable::Component(_) => val,
+    };
+    match app.input_param {
+        1 => app.sweep_start = to_internal(val),
+        2 => app.sweep_stop = to_internal(val),
+        3 => app.sweep_step = match app.sweep_variable {
+            Variable::Temperature => val.abs() / units::get_temperature_difference(1.0, app.units.temp),
+            _ => to_internal(val.abs()),
+        },
+        _ => {}
+    }
+    app.sweep_scroll = 0;
+    app.sweep_message.clear();
+}
+
+pub fn run_sweep(app: &App, result: &Result<Sweep, &'static str>) -> Vec<ListItem<'static>> {
+    let unit = variable_unit(app);
+    let step = match app.sweep_variable {
+        Variable::Temperature => units::get_temperature_difference(app.sweep_step, app.units.temp),
+        _ => get_variable(app, app.sweep_step),
+    };
+    let selected = PROPERTIES
+        .iter()
+        .zip(app.sweep_props.iter())
+        .filter(|(_, on)| **on)
+        .count();
+    let mut items = vec![
+        ListItem::new(
+            format!("{:<24} {} ({})",
+                "Variable:", app.sweep_variable.name(), app.model.name(),
+            )
+        )
+            .fg(Color::LightYellow)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "1-Start:", get_variable(app, app.sweep_start), unit,
+            )
+        )
+            .fg(Color::LightYellow)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "2-Stop:", get_variable(app, app.sweep_stop), unit,
+            )
+        )
+            .fg(Color::LightYellow)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "3-Step:", step, unit,
+            )
+        )
+            .fg(Color::LightYellow)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {} [{}]   ({} selected)",
+                "Output Property:",
+                PROPERTIES[app.sweep_cursor],
+                if app.sweep_props[app.sweep_cursor] { "x" } else { " " },
+                selected,
+            )
+        )
+            .fg(Color::LightYellow)
+            .bg(Color::Black),
+    ];
+
+    let status = match result {
+        Err(msg) => ListItem::new(msg.to_string()).fg(Color::Red),
+        Ok(_) if !app.sweep_message.is_empty() => ListItem::new(app.sweep_message.clone()).fg(Color::White),
+        Ok(sweep) => ListItem::new(format!("{} points", sweep.rows.len())).fg(Color::White),
+    };
+    items.push(status.bg(Color::Black));
+    items
+}
+
+pub fn sweep_table(app: &mut App, result: &Result<Sweep, &'static str>) -> Table<'static> {
+    let Ok(sweep) = result else {
+        return Table::default()
+    };
+    app.sweep_scroll = app.sweep_scroll.min(sweep.rows.len().saturating_sub(1));
+    let rows: Vec<Row<'static>> = sweep.rows
+        .iter()
+        .skip(app.sweep_scroll)
+        .map(|row| {
+            Row::new(
+                row.iter()
+                    .map(|val| if val.is_nan() { "n/a".to_string() } else { format!("{:.4}", val) })
+                    .collect::<Vec<String>>()
+            )
+                .style(Style::new().fg(Color::LightCyan).bg(Color::Black))
+        })
+        .collect();
+    let widths: Vec<Constraint> = sweep.headers
+        .iter()
+        .map(|header| Constraint::Length(header.len().max(COLUMN_WIDTH) as u16))
+        .collect();
+    Table::new(rows, widths)
+        .header(
+            Row::new(sweep.headers.clone())
+                .style(Style::new().fg(Color::White).bg(Color::Blue))
+        )
+        .style(Style::new().bg(Color::Black))
+}
@@ -0,0 +1,145 @@
+use ratatui::widgets::ListItem;
+use ratatui::
+    style::{
+        Color,
+        Stylize
+    };
+
+use crate::flash::{
+    self,
+    GasProps,
+};
+use crate::units::{
+    self,
+    PrintUnit,
+};
+use crate::{
+    App,
+    recalculate,
+};
+
+// Throttling starts from the inlet state when set, otherwise the current state
+pub fn throttle_inlet(app: &App) -> GasProps {
+    if app.show_inlet_state {
+        app.inlet_state
+    } else {
+        app.cur_state
+    }
+}
+
+// Isenthalpic outlet state at the downstream pressure
+pub fn throttle_outlet(app: &App, inlet: &GasProps) -> GasProps {
+    flash::props_ph(&app.gas_comp, app.model, app.throttle_p2, inlet.h, inlet.t)
+}
+
+// Integral Joule-Thomson coefficient (K/kPa) over the pressure drop
+pub fn integral_jt_coeff(inlet: &GasProps, outlet: &GasProps) -> f64 {
+    let dp = outlet.p - inlet.p;
+    if dp == 0.0 {
+        return inlet.jt
+    }
+    (outlet.t - inlet.t) / dp
+}
+
+pub fn set_param(app: &mut App, val: f64) {
+    if app.input_param == 1 {
+        app.throttle_p2 = units::set_pressure(val, app.units.pressure);
+    }
+}
+
+// Loads the throttled state into the outlet state
+pub fn set_outlet(app: &mut App) {
+    let inlet = throttle_inlet(app);
+    let outlet = throttle_outlet(app, &inlet);
+    app.outlet_state.p = outlet.p;
+    app.outlet_state.t = outlet.t;
+    recalculate(app);
+    app.show_outlet_state = true;
+}
+
+pub fn run_throttle(app: &App) -> Vec<ListItem<'static>> {
+    let inlet = throttle_inlet(app);
+    let outlet = throttle_outlet(app, &inlet);
+    let t1 = units::get_temperature(inlet.t, app.units.temp);
+    let t2 = units::get_temperature(outlet.t, app.units.temp);
+    let p_str = app.units.pressure.print_unit();
+    let t_str = app.units.temp.print_unit();
+    let jt_str = app.units.jt_coeff.print_unit();
+
+    let inlet_text = if app.show_inlet_state { "Inlet State" } else { "Current State" };
+    let drop_color = if t2 < t1 { Color::LightCyan } else { Color::LightRed };
+
+    vec![
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "1-Downstream Press:", units::get_pressure(app.throttle_p2, app.units.pressure), p_str,
+            )
+        )
+            .fg(Color::LightYellow)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {}",
+                "Upstream From:", inlet_text,
+            )
+        )
+            .fg(Color::White)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Upstream Press:", units::get_pressure(inlet.p, app.units.pressure), p_str,
+            )
+        )
+            .fg(Color::White)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Upstream Temp:", t1, t_str,
+            )
+        )
+            .fg(Color::White)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Outlet Temp (h=const):", t2, t_str,
+            )
+        )
+            .fg(Color::LightCyan)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "Temp Drop:", t1 - t2, t_str,
+            )
+        )
+            .fg(drop_color)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.6} {}",
+                "Integral JT Coeff:", units::get_jt_coeff(integral_jt_coeff(&inlet, &outlet), app.units.jt_coeff), jt_str,
+            )
+        )
+            .fg(Color::LightCyan)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.6} {}",
+                "Point JT Coeff (in):", units::get_jt_coeff(inlet.jt, app.units.jt_coeff), jt_str,
+            )
+        )
+            .fg(Color::LightCyan)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.6} {}",
+                "Point JT Coeff (out):", units::get_jt_coeff(outlet.jt, app.units.jt_coeff), jt_str,
+            )
+        )
+            .fg(Color::LightCyan)
+            .bg(Color::Black),
+    ]
+}
@@ -0,0 +1,239 @@
+use std::f64::consts::FRAC_2_PI;
+
+use aga8::composition::Composition;
+
+use crate::eos::Model;
+use crate::flash;
+use crate::gas::components;
+
+// Gas constant in J/(mol-K)
+const R: f64 = 8.314462618;
+
+// Critical constants for the Chung et al. method in the order of
+// gas::components (Poling, Prausnitz & O'Connell)
+// (Tc K, Vc cm^3/mol, acentric factor, dipole moment debye, association factor, molar mass g/mol)
+const CHUNG_DATA: [(f64, f64, f64, f64, f64, f64); 21] = [
+    (190.56, 98.6, 0.011, 0.0, 0.0, 16.043),
+    (126.20, 90.1, 0.037, 0.0, 0.0, 28.014),
+    (304.12, 94.07, 0.225, 0.0, 0.0, 44.010),
+    (305.32, 145.5, 0.099, 0.0, 0.0, 30.070),
+    (369.83, 200.0, 0.152, 0.0, 0.0, 44.097),
+    (407.85, 262.7, 0.186, 0.1, 0.0, 58.123),
+    (425.12, 255.0, 0.200, 0.0, 0.0, 58.123),
+    (460.39, 308.3, 0.229, 0.1, 0.0, 72.150),
+    (469.70, 313.0, 0.252, 0.0, 0.0, 72.150),
+    (507.60, 371.0, 0.300, 0.0, 0.0, 86.177),
+    (540.20, 428.0, 0.350, 0.0, 0.0, 100.204),
+    (568.70, 492.0, 0.399, 0.0, 0.0, 114.231),
+    (594.60, 555.0, 0.445, 0.0, 0.0, 128.258),
+    (617.70, 624.0, 0.490, 0.0, 0.0, 142.285),
+    (33.19, 64.1, -0.216, 0.0, 0.0, 2.016),
+    (154.58, 73.4, 0.022, 0.0, 0.0, 31.999),
+    (132.85, 93.1, 0.045, 0.1, 0.0, 28.010),
+    (647.14, 55.95, 0.344, 1.8, 0.075908, 18.015),
+    (373.40, 98.0, 0.090, 0.9, 0.0, 34.082),
+    (5.19, 57.4, -0.390, 0.0, 0.0, 4.003),
+    (150.86, 74.57, -0.002, 0.0, 0.0, 39.948),
+];
+
+// Dense fluid viscosity coefficients (a, b, c, d)
+const VISCOSITY_COEFFS: [(f64, f64, f64, f64); 10] = [
+    (6.324, 50.412, -51.680, 1189.0),
+    (1.210e-3, -1.154e-3, -6.257e-3, 0.03728),
+    (5.283, 254.209, -168.48, 3898.0),
+    (6.623, 38.096, -8.464, 31.42),
+    (19.745, 7.630, -14.354, 31.53),
+    (-1.900, -12.537, 4.985, -18.15),
+    (24.275, 3.450, -11.291, 69.35),
+    (0.7972, 1.117, 0.01235, -4.117),
+    (-0.2382, 0.06770, -0.8163, 4.025),
+    (0.06863, 0.3479, 0.5926, -0.727),
+];
+
+// Dense fluid thermal conductivity coefficients (a, b, c, d)
+const CONDUCTIVITY_COEFFS: [(f64, f64, f64, f64); 7] = [
+    (2.4166, 0.74824, -0.91858, 121.72),
+    (-0.50924, -1.5094, -49.991, 69.983),
+    (6.6107, 5.6207, 64.760, 27.039),
+    (14.543, -8.9139, -5.6379, 74.344),
+    (0.79274, 0.82019, -0.69369, 6.3173),
+    (-5.8634, 12.801, 9.5893, 65.529),
+    (91.089, 128.11, -54.217, 523.81),
+];
+
+pub struct Transport {
+    // Dynamic viscosity in Pa-s
+    pub viscosity: f64,
+    // Kinematic viscosity in m^2/s
+    pub kinematic_viscosity: f64,
+    // Thermal conductivity in W/(m-K)
+    pub conductivity: f64,
+    pub prandtl: f64,
+}
+
+// Pseudo-critical mixture parameters from the Chung mixing rules
+struct ChungMixture {
+    tc: f64,
+    vc: f64,
+    omega: f64,
+    mm: f64,
+    mu_r: f64,
+    kappa: f64,
+}
+
+fn chung_mixture(gas_comp: &Composition) -> ChungMixture {
+    let x: Vec<f64> = components(gas_comp).iter().map(|(_, x)| *x).collect();
+    let mut sigma3 = 0.0;
+    let mut eps_sigma3 = 0.0;
+    let mut omega_sigma3 = 0.0;
+    let mut eps_sigma2_mm = 0.0;
+    let mut dipole = 0.0;
+    let mut kappa = 0.0;
+    for (x_i, data_i) in x.iter().zip(CHUNG_DATA.iter()) {
+        if *x_i == 0.0 {
+            continue;
+        }
+        let (tc_i, vc_i, w_i, mu_i, k_i, mm_i) = *data_i;
+        for (x_j, data_j) in x.iter().zip(CHUNG_DATA.iter()) {
+            if *x_j == 0.0 {
+                continue;
+            }
+            let (tc_j, vc_j, w_j, mu_j, k_j, mm_j) = *data_j;
+            let xx = x_i * x_j;
+            let sigma_ij = (0.809 * vc_i.cbrt() * 0.809 * vc_j.cbrt()).sqrt();
+            let eps_ij = (tc_i / 1.2593 * tc_j / 1.2593).sqrt();
+            let mm_ij = 2.0 * mm_i * mm_j / (mm_i + mm_j);
+            sigma3 += xx * sigma_ij.powi(3);
+            eps_sigma3 += xx * eps_ij * sigma_ij.powi(3);
+            omega_sigma3 += xx * (w_i + w_j) / 2.0 * sigma_ij.powi(3);
+            eps_sigma2_mm += xx * eps_ij * sigma_ij.powi(2) * mm_ij.sqrt();
+            dipole += xx * mu_i.powi(2) * mu_j.powi(2) / sigma_ij.powi(3);
+            kappa += xx * (k_i * k_j).sqrt();
+        }
+    }
+    let sigma = sigma3.cbrt();
+    let eps = eps_sigma3 / sigma3;
+    let mm = (eps_sigma2_mm / (eps * sigma * sigma)).powi(2);
+    let omega = omega_sigma3 / sigma3;
+    let mu = (sigma3 * dipole).powf(0.25);
+    let vc = (sigma / 0.809).powi(3);
+    let tc = 1.2593 * eps;
+    let mu_r = 131.3 * mu / (vc * tc).sqrt();
+    ChungMixture {
+        tc,
+        vc,
+        omega,
+        mm,
+        mu_r,
+        kappa,
+    }
+}
+
+// Neufeld collision integral
+fn collision_integral(t_star: f64) -> f64 {
+    1.16145 * t_star.powf(-0.14874)
+        + 0.52487 * (-0.77320 * t_star).exp()
+        + 2.16178 * (-2.43787 * t_star).exp()
+}
+
+fn coefficient(abcd: (f64, f64, f64, f64), mix: &ChungMixture) -> f64 {
+    let (a, b, c, d) = abcd;
+    a + b * mix.omega + c * mix.mu_r.powi(4) + d * mix.kappa
+}
+
+// Viscosity (Pa-s) and thermal conductivity (W/(m-K)) by Chung et al. at
+// temperature t (K) and molar density d (mol/l)
+fn chung(gas_comp: &Composition, model: Model, t: f64, d: f64) -> (f64, f64) {
+    let mix = chung_mixture(gas_comp);
+    let t_star = 1.2593 * t / mix.tc;
+    let omega_v = collision_integral(t_star);
+    let fc = 1.0 - 0.2756 * mix.omega + 0.059035 * mix.mu_r.powi(4) + mix.kappa;
+    let y = d / 1000.0 * mix.vc / 6.0;
+    let g1 = (1.0 - 0.5 * y) / (1.0 - y).powi(3);
+
+    // Viscosity in micropoise
+    let e: Vec<f64> = VISCOSITY_COEFFS.iter().map(|abcd| coefficient(*abcd, &mix)).collect();
+    let eta_0 = 40.785 * fc * (mix.mm * t).sqrt() / (mix.vc.powf(2.0 / 3.0) * omega_v);
+    let eta = if y > 0.0 {
+        let g2 = (e[0] * (1.0 - (-e[3] * y).exp()) / y + e[1] * g1 * (e[4] * y).exp() + e[2] * g1)
+            / (e[0] * e[3] + e[1] + e[2]);
+        let eta_kk = e[6] * y * y * g2 * (e[7] + e[8] / t_star + e[9] / (t_star * t_star)).exp();
+        let eta_k = t_star.sqrt() / omega_v * fc * (1.0 / g2 + e[5] * y) + eta_kk;
+        eta_k * 36.344 * (mix.mm * mix.tc).sqrt() / mix.vc.powf(2.0 / 3.0)
+    } else {
+        eta_0
+    };
+
+    // Thermal conductivity from the ideal gas heat capacity
+    let cv0 = flash::props_pt(gas_comp, model, 1.0e-3, t).cv;
+    let alpha = cv0 / R - 1.5;
+    let beta = 0.7862 - 0.7109 * mix.omega + 1.3168 * mix.omega * mix.omega;
+    let tr = t / mix.tc;
+    let z = 2.0 + 10.5 * tr * tr;
+    let psi = 1.0 + alpha * (0.215 + 0.28288 * alpha - 1.061 * beta + 0.26665 * z)
+        / (FRAC_2_PI + beta * z + 1.061 * alpha * beta);
+    let mm_kg = mix.mm / 1000.0;
+    let b: Vec<f64> = CONDUCTIVITY_COEFFS.iter().map(|abcd| coefficient(*abcd, &mix)).collect();
+    let lambda_0 = 31.2 * eta_0 * 1.0e-7 * psi / mm_kg;
+    let lambda = if y > 0.0 {
+        let g2 = (b[0] / y * (1.0 - (-b[3] * y).exp()) + b[1] * g1 * (b[4] * y).exp() + b[2] * g1)
+            / (b[0] * b[3] + b[1] + b[2]);
+        let q = 3.586e-3 * (mix.tc / mm_kg).sqrt() / mix.vc.powf(2.0 / 3.0);
+        lambda_0 * (1.0 / g2 + b[5] * y) + q * b[6] * y * y * tr.sqrt() * g2
+    } else {
+        lambda_0
+    };
+
+    (eta * 1.0e-7, lambda)
+}
+
+// Transport properties at temperature (K), molar density (mol/l), molar
+// mass (g/mol) and isobaric heat capacity (J/(mol-K))
+pub fn transport_properties(gas_comp: &Composition, model: Model, t: f64, d: f64, mm: f64, cp: f64) -> Transport {
+    let (viscosity, conductivity) = chung(gas_comp, model, t, d);
+    let density_kg_m3 = d * mm;
+    let cp_j_kg_k = cp / mm * 1000.0;
+    Transport {
+        viscosity,
+        kinematic_viscosity: viscosity / density_kg_m3,
+        conductivity,
+        prandtl: cp_j_kg_k * viscosity / conductivity,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gas::pure_component;
+
+    fn transport_at(index: usize, p: f64, t: f64) -> Transport {
+        let comp = pure_component(index);
+        let props = flash::props_pt(&comp, Model::Gerg2008, p, t);
+        transport_properties(&comp, Model::Gerg2008, t, props.d, props.mm, props.cp)
+    }
+
+    // Dilute gas values at 300 K and 101.325 kPa (NIST), as (component
+    // index, viscosity uPa-s, conductivity mW/(m-K)); Chung et al. quote
+    // errors of a few percent for nonpolar gases
+    #[test]
+    fn dilute_gas_reference_values() {
+        for (index, viscosity, conductivity) in [
+            (0, 11.19, 34.3),
+            (1, 17.90, 25.97),
+            (2, 15.02, 16.8),
+            (3, 9.35, 21.2),
+        ] {
+            let tp = transport_at(index, 101.325, 300.0);
+            assert!((tp.viscosity * 1.0e6 / viscosity - 1.0).abs() < 0.03, "viscosity {} {}", index, tp.viscosity);
+            assert!((tp.conductivity * 1.0e3 / conductivity - 1.0).abs() < 0.05, "conductivity {} {}", index, tp.conductivity);
+        }
+    }
+
+    #[test]
+    fn dense_gas_correction_raises_both() {
+        let dilute = transport_at(0, 101.325, 300.0);
+        let dense = transport_at(0, 10000.0, 300.0);
+        assert!(dense.viscosity > dilute.viscosity);
+        assert!(dense.conductivity > dilute.conductivity);
+    }
+}
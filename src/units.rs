@@ -1,4 +1,7 @@
-use aga8::{composition::Composition, gerg2008};
+use aga8::composition::Composition;
+
+use crate::eos::Model;
+use crate::flash;
 
 
 pub struct Units {
@@ -11,6 +14,9 @@ pub struct Units {
     pub jt_coeff: JT_Coeff,
     pub flow: Flow,
     pub length: Length,
+    pub power: Power,
+    pub viscosity: Viscosity,
+    pub conductivity: Conductivity,
 }
 impl Default for Units {
     fn default() -> Self {
@@ -24,6 +30,9 @@ impl Default for Units {
             jt_coeff: JT_Coeff::R_PSI,
             flow: Flow::scfm,
             length: Length::inch,
+            power: Power::hp,
+            viscosity: Viscosity::cP,
+            conductivity: Conductivity::BTU_hr_ft_F,
         }
     }
 }
@@ -48,6 +57,16 @@ impl PrintUnit for Pressure {
         }
     }
 }
+impl Pressure {
+    // Gauge pressure unit paired with each pressure unit
+    pub fn print_gauge_unit(&self) -> &'static str{
+        match self {
+            Pressure::kPa => "kPag",
+            Pressure::Bar => "barg",
+            Pressure::PSI => "psig",
+        }
+    }
+}
 
 #[derive(Clone, Copy)]
 pub enum Temperature {
@@ -82,6 +101,16 @@ impl PrintUnit for Density {
         }
     }
 }
+impl Density {
+    // Specific volume unit paired with each density unit
+    pub fn print_volume_unit(&self) -> &'static str{
+        match self {
+           Density::mol_l => "l/mol",
+           Density::kg_m3 => "m^3/kg",
+           Density::lbm_ft3 => "ft^3/lbm",
+        }
+    }
+}
 
 #[derive(Clone, Copy)]
 pub enum Energy {
@@ -174,6 +203,34 @@ impl PrintUnit for Flow {
         }
     }
 }
+impl Flow {
+    pub fn is_imperial(&self) -> bool {
+        matches!(self, Flow::lbm_s | Flow::lbm_m | Flow::lbm_h | Flow::scfm | Flow::scfh)
+    }
+
+    // Mass flow unit matching the selected unit, for rows that are always
+    // reported on a mass basis
+    pub fn mass_unit(&self) -> Flow {
+        match self {
+            Flow::Nm3_h => Flow::kg_h,
+            Flow::scfm => Flow::lbm_m,
+            Flow::scfh => Flow::lbm_h,
+            unit => *unit,
+        }
+    }
+
+    // Standard volume units for rows that are always reported on a volume
+    // basis, the selected unit when it is one and otherwise both scfh and
+    // Nm^3/hr
+    pub fn std_volume_units(&self) -> &'static [Flow] {
+        match self {
+            Flow::Nm3_h => &[Flow::Nm3_h],
+            Flow::scfm => &[Flow::scfm],
+            Flow::scfh => &[Flow::scfh],
+            _ => &[Flow::scfh, Flow::Nm3_h],
+        }
+    }
+}
 
 #[derive(Clone, Copy)]
 pub enum Length {
@@ -194,6 +251,71 @@ impl PrintUnit for Length {
         }
     }
 }
+impl Length {
+    // Unit for pipeline lengths and elevations paired with each length unit
+    pub fn print_distance_unit(&self) -> &'static str{
+        match self {
+           Length::mm | Length::cm | Length::m => "m",
+           Length::inch | Length::ft => "ft",
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum Power {
+    kW,
+    hp,
+    BTU_h,
+}
+impl PrintUnit for Power {
+    fn print_unit(&self) -> &'static str{
+        match self {
+           Power::kW => "kW",
+           Power::hp => "hp",
+           Power::BTU_h => "BTU/hr",
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum Viscosity {
+    cP,
+    Pa_s,
+    lbm_ft_s,
+}
+impl PrintUnit for Viscosity {
+    fn print_unit(&self) -> &'static str{
+        match self {
+           Viscosity::cP => "cP",
+           Viscosity::Pa_s => "Pa-s",
+           Viscosity::lbm_ft_s => "lbm/(ft-s)",
+        }
+    }
+}
+impl Viscosity {
+    // Kinematic viscosity unit paired with each dynamic viscosity unit
+    pub fn print_kinematic_unit(&self) -> &'static str{
+        match self {
+           Viscosity::cP => "cSt",
+           Viscosity::Pa_s => "m^2/s",
+           Viscosity::lbm_ft_s => "ft^2/s",
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum Conductivity {
+    W_m_K,
+    BTU_hr_ft_F,
+}
+impl PrintUnit for Conductivity {
+    fn print_unit(&self) -> &'static str{
+        match self {
+           Conductivity::W_m_K => "W/(m-K)",
+           Conductivity::BTU_hr_ft_F => "BTU/(hr-ft-F)",
+        }
+    }
+}
 
 pub fn get_pressure(pressure: f64, unit: Pressure) -> f64 {
     match unit {
@@ -229,6 +351,15 @@ pub fn set_temperature(temperature: f64, unit: Temperature) -> f64 {
     }
 }
 
+pub fn get_temperature_difference(temperature: f64, unit: Temperature) -> f64 {
+    match unit {
+        Temperature::K => temperature,
+        Temperature::C => temperature,
+        Temperature::F => temperature * 9.0 / 5.0,
+        Temperature::R => temperature * 9.0 / 5.0,
+    }
+}
+
 pub fn get_density(density: f64, unit: Density, molar_mass: f64) -> f64 {
     match unit {
         Density::mol_l => density,
@@ -237,6 +368,11 @@ pub fn get_density(density: f64, unit: Density, molar_mass: f64) -> f64 {
     }
 }
 
+// Specific volume from l/mol, in the reciprocal of the density unit
+pub fn get_specific_volume(volume: f64, unit: Density, molar_mass: f64) -> f64 {
+    volume / get_density(1.0, unit, molar_mass)
+}
+
 pub fn get_energy(energy: f64, unit: Energy, molar_mass: f64) -> f64 {
     match unit {
         Energy::J_mol => energy,
@@ -283,6 +419,37 @@ pub fn set_speed(speed:f64, unit: Speed) -> f64 {
     }
 }
 
+pub fn get_power(power_kw: f64, unit: Power) -> f64 {
+    match unit {
+        Power::kW => power_kw,
+        Power::hp => power_kw * 1.34102,
+        Power::BTU_h => power_kw * 3412.14,
+    }
+}
+
+pub fn get_viscosity(viscosity_pa_s: f64, unit: Viscosity) -> f64 {
+    match unit {
+        Viscosity::cP => viscosity_pa_s * 1000.0,
+        Viscosity::Pa_s => viscosity_pa_s,
+        Viscosity::lbm_ft_s => viscosity_pa_s * 0.671969,
+    }
+}
+
+pub fn get_kinematic_viscosity(viscosity_m2_s: f64, unit: Viscosity) -> f64 {
+    match unit {
+        Viscosity::cP => viscosity_m2_s * 1.0e6,
+        Viscosity::Pa_s => viscosity_m2_s,
+        Viscosity::lbm_ft_s => viscosity_m2_s * 10.7639,
+    }
+}
+
+pub fn get_conductivity(conductivity_w_m_k: f64, unit: Conductivity) -> f64 {
+    match unit {
+        Conductivity::W_m_K => conductivity_w_m_k,
+        Conductivity::BTU_hr_ft_F => conductivity_w_m_k * 0.577789,
+    }
+}
+
 pub fn get_gibbs_energy(g: f64, p: Pressure, t: Temperature) -> f64 {
     let mut val = g;
     match p {
@@ -299,6 +466,26 @@ pub fn get_gibbs_energy(g: f64, p: Pressure, t: Temperature) -> f64 {
     val
 }
 
+// Isothermal compressibility from 1/kPa to 1/(pressure unit)
+pub fn get_compressibility(compressibility: f64, unit: Pressure) -> f64 {
+    compressibility / get_pressure(1.0, unit)
+}
+
+// Volume expansivity from 1/K to 1/(temperature unit)
+pub fn get_expansivity(expansivity: f64, unit: Temperature) -> f64 {
+    expansivity / get_temperature_difference(1.0, unit)
+}
+
+// dP/dT from kPa/K to (pressure unit)/(temperature unit)
+pub fn get_dp_dt(dp_dt: f64, p: Pressure, t: Temperature) -> f64 {
+    get_pressure(dp_dt, p) / get_temperature_difference(1.0, t)
+}
+
+// dP/dD from kPa/(mol/l) to (pressure unit)/(density unit)
+pub fn get_dp_dd(dp_dd: f64, p: Pressure, d: Density, molar_mass: f64) -> f64 {
+    get_pressure(dp_dd, p) / get_density(1.0, d, molar_mass)
+}
+
 pub fn get_jt_coeff(jt_coeff:f64, unit: JT_Coeff) -> f64 {
     match unit {
         JT_Coeff::K_kPa => jt_coeff,
@@ -315,7 +502,13 @@ pub fn set_jt_coeff(jt_coeff:f64, unit: JT_Coeff) -> f64 {
     }
 }
 
-pub fn get_flow(flow_kg_s: f64, unit: Flow, gas_comp: &Composition, stp_60: bool, use_gerg2008: bool) -> f64 {
+// Density (kg/m^3) at 101.325 kPa and temperature t (K)
+fn std_density(gas_comp: &Composition, model: Model, t: f64) -> f64 {
+    let props = flash::props_pt(gas_comp, model, 101.325, t);
+    props.mm * props.d
+}
+
+pub fn get_flow(flow_kg_s: f64, unit: Flow, gas_comp: &Composition, stp_60: bool, model: Model) -> f64 {
     match unit {
         Flow::kg_s => flow_kg_s,
         Flow::kg_m => flow_kg_s * 60.0,
@@ -326,71 +519,20 @@ pub fn get_flow(flow_kg_s: f64, unit: Flow, gas_comp: &Composition, stp_60: bool
         Flow::Nm3_h => {
             let kg_h = flow_kg_s * 3600.0;
             // Calculate density at STP
-            let density_kg_m3;
-            if use_gerg2008 {
-                let mut gas_state = gerg2008::Gerg2008::new();
-                gas_state.set_composition(gas_comp);
-                gas_state.p = 101.325;
-                gas_state.t = 273.15;
-                gas_state.density(0);
-                gas_state.properties();
-                density_kg_m3 = gas_state.mm * gas_state.d;
-            } else {
-                let mut gas_state = aga8::detail::Detail::new();
-                gas_state.set_composition(gas_comp);
-                gas_state.p = 101.325;
-                gas_state.t = 273.15;
-                gas_state.density();
-                gas_state.properties();
-                density_kg_m3 = gas_state.mm * gas_state.d;
-            }
+            let density_kg_m3 = std_density(gas_comp, model, 273.15);
             kg_h / density_kg_m3
         },
         Flow::scfm => {
             if stp_60 {
                 let kg_m = flow_kg_s * 60.0;
                 // Calculate density at STP
-                let density_kg_m3;
-                if use_gerg2008 {
-                    let mut gas_state = gerg2008::Gerg2008::new();
-                    gas_state.set_composition(gas_comp);
-                    gas_state.p = 101.325;
-                    gas_state.t = 288.706;
-                    gas_state.density(0);
-                    gas_state.properties();
-                    density_kg_m3 = gas_state.mm * gas_state.d;
-                } else {
-                    let mut gas_state = aga8::detail::Detail::new();
-                    gas_state.set_composition(gas_comp);
-                    gas_state.p = 101.325;
-                    gas_state.t = 288.706;
-                    gas_state.density();
-                    gas_state.properties();
-                    density_kg_m3 = gas_state.mm * gas_state.d;
-                }
+                let density_kg_m3 = std_density(gas_comp, model, 288.706);
                 let m3_min = kg_m / density_kg_m3;
                 m3_min * 35.3147
             } else {
                 let kg_m = flow_kg_s * 60.0;
                 // Calculate density at STP
-                let density_kg_m3;
-                if use_gerg2008 {
-                    let mut gas_state = gerg2008::Gerg2008::new();
-                    gas_state.set_composition(gas_comp);
-                    gas_state.p = 101.325;
-                    gas_state.t = 294.261;
-                    gas_state.density(0);
-                    gas_state.properties();
-                    density_kg_m3 = gas_state.mm * gas_state.d;
-                } else {
-                    let mut gas_state = aga8::detail::Detail::new();
-                    gas_state.set_composition(gas_comp);
-                    gas_state.p = 101.325;
-                    gas_state.t = 294.261;
-                    gas_state.density();
-                    gas_state.properties();
-                    density_kg_m3 = gas_state.mm * gas_state.d;
-                }
+                let density_kg_m3 = std_density(gas_comp, model, 294.261);
                 let m3_min = kg_m / density_kg_m3;
                 m3_min * 35.3147
             }
@@ -399,47 +541,13 @@ pub fn get_flow(flow_kg_s: f64, unit: Flow, gas_comp: &Composition, stp_60: bool
             if stp_60 {
                 let kg_hr = flow_kg_s * 3600.0;
                 // Calculate density at STP
-                let density_kg_m3;
-                if use_gerg2008 {
-                    let mut gas_state = gerg2008::Gerg2008::new();
-                    gas_state.set_composition(gas_comp);
-                    gas_state.p = 101.325;
-                    gas_state.t = 288.706;
-                    gas_state.density(0);
-                    gas_state.properties();
-                    density_kg_m3 = gas_state.mm * gas_state.d;
-                } else {
-                    let mut gas_state = aga8::detail::Detail::new();
-                    gas_state.set_composition(gas_comp);
-                    gas_state.p = 101.325;
-                    gas_state.t = 288.706;
-                    gas_state.density();
-                    gas_state.properties();
-                    density_kg_m3 = gas_state.mm * gas_state.d;
-                }
+                let density_kg_m3 = std_density(gas_comp, model, 288.706);
                 let m3_hr = kg_hr / density_kg_m3;
                 m3_hr * 35.3147
             } else {
                 let kg_hr = flow_kg_s * 3600.0;
                 // Calculate density at STP
-                let density_kg_m3;
-                if use_gerg2008 {
-                    let mut gas_state = gerg2008::Gerg2008::new();
-                    gas_state.set_composition(gas_comp);
-                    gas_state.p = 101.325;
-                    gas_state.t = 294.261;
-                    gas_state.density(0);
-                    gas_state.properties();
-                    density_kg_m3 = gas_state.mm * gas_state.d;
-                } else {
-                    let mut gas_state = aga8::detail::Detail::new();
-                    gas_state.set_composition(gas_comp);
-                    gas_state.p = 101.325;
-                    gas_state.t = 294.261;
-                    gas_state.density();
-                    gas_state.properties();
-                    density_kg_m3 = gas_state.mm * gas_state.d;
-                }
+                let density_kg_m3 = std_density(gas_comp, model, 294.261);
                 let m3_hr = kg_hr / density_kg_m3;
                 m3_hr * 35.3147
             }
@@ -447,7 +555,7 @@ pub fn get_flow(flow_kg_s: f64, unit: Flow, gas_comp: &Composition, stp_60: bool
     }
 }
 
-pub fn set_flow(flow: f64, unit: Flow, gas_comp: &Composition, stp_60: bool, use_gerg2008: bool) -> f64 {
+pub fn set_flow(flow: f64, unit: Flow, gas_comp: &Composition, stp_60: bool, model: Model) -> f64 {
     match unit {
         Flow::kg_s => flow,
         Flow::kg_m => flow / 60.0,
@@ -456,140 +564,105 @@ pub fn set_flow(flow: f64, unit: Flow, gas_comp: &Composition, stp_60: bool, use
         Flow::lbm_m => flow / 2.20462 / 60.0,
         Flow::lbm_h => flow / 2.20462 / 3600.0,
         Flow::Nm3_h => {
-            let Nm3_s = flow / 60.0;
-            let kg_s;
+            let Nm3_s = flow / 3600.0;
             // Calculate density at STP
-            if use_gerg2008 {
-                let mut gas_state = gerg2008::Gerg2008::new();
-                gas_state.set_composition(gas_comp);
-                gas_state.p = 101.325;
-                gas_state.t = 273.15;
-                gas_state.density(0);
-                gas_state.properties();
-                let density_kg_m3 = gas_state.mm * gas_state.d;
-                kg_s = density_kg_m3 * Nm3_s;
-
-            } else {
-                let mut gas_state = aga8::detail::Detail::new();
-                gas_state.set_composition(gas_comp);
-                gas_state.p = 101.325;
-                gas_state.t = 273.15;
-                gas_state.density();
-                gas_state.properties();
-                let density_kg_m3 = gas_state.mm * gas_state.d;
-                kg_s = density_kg_m3 * Nm3_s;
-            }
-            kg_s
+            let density_kg_m3 = std_density(gas_comp, model, 273.15);
+            density_kg_m3 * Nm3_s
         },
         Flow::scfm => {
             if stp_60 {
                 let scfs = flow / 60.0;
                 // Calculate density at STP
-                let kg_s;
-                if use_gerg2008 {
-                    let mut gas_state = gerg2008::Gerg2008::new();
-                    gas_state.set_composition(gas_comp);
-                    gas_state.p = 101.325;
-                    gas_state.t = 288.706;
-                    gas_state.density(0);
-                    gas_state.properties();
-                    let density_kg_m3 = gas_state.mm * gas_state.d;
-                    let density_kg_ft3 = density_kg_m3 / density_kg_m3;
-                    kg_s = density_kg_ft3 * scfs;
-                } else {
-                    let mut gas_state = aga8::detail::Detail::new();
-                    gas_state.set_composition(gas_comp);
-                    gas_state.p = 101.325;
-                    gas_state.t = 288.706;
-                    gas_state.density();
-                    gas_state.properties();
-                    let density_kg_m3 = gas_state.mm * gas_state.d;
-                    let density_kg_ft3 = density_kg_m3 / density_kg_m3;
-                    kg_s = density_kg_ft3 * scfs;
-                }
-                kg_s
+                let density_kg_m3 = std_density(gas_comp, model, 288.706);
+                let density_kg_ft3 = density_kg_m3 / 35.3147;
+                density_kg_ft3 * scfs
             } else {
                 let scfs = flow / 60.0;
                 // Calculate density at STP
-                let kg_s;
-                if use_gerg2008 {
-                    let mut gas_state = gerg2008::Gerg2008::new();
-                    gas_state.set_composition(gas_comp);
-                    gas_state.p = 101.325;
-                    gas_state.t = 294.261;
-                    gas_state.density(0);
-                    gas_state.properties();
-                    let density_kg_m3 = gas_state.mm * gas_state.d;
-                    let density_kg_ft3 = density_kg_m3 / density_kg_m3;
-                    kg_s = density_kg_ft3 * scfs;
-                } else {
-                    let mut gas_state = aga8::detail::Detail::new();
-                    gas_state.set_composition(gas_comp);
-                    gas_state.p = 101.325;
-                    gas_state.t = 294.261;
-                    gas_state.density();
-                    gas_state.properties();
-                    let density_kg_m3 = gas_state.mm * gas_state.d;
-                    let density_kg_ft3 = density_kg_m3 / density_kg_m3;
-                    kg_s = density_kg_ft3 * scfs;
-                }
-                kg_s
+                let density_kg_m3 = std_density(gas_comp, model, 294.261);
+                let density_kg_ft3 = density_kg_m3 / 35.3147;
+                density_kg_ft3 * scfs
             }
         },
         Flow::scfh => {
             if stp_60 {
                 let scfs = flow / 3600.0;
                 // Calculate density at STP
-                let kg_s;
-                if use_gerg2008 {
-                    let mut gas_state = gerg2008::Gerg2008::new();
-                    gas_state.set_composition(gas_comp);
-                    gas_state.p = 101.325;
-                    gas_state.t = 288.706;
-                    gas_state.density(0);
-                    gas_state.properties();
-                    let density_kg_m3 = gas_state.mm * gas_state.d;
-                    let density_kg_ft3 = density_kg_m3 / density_kg_m3;
-                    kg_s = density_kg_ft3 * scfs;
-                } else {
-                    let mut gas_state = aga8::detail::Detail::new();
-                    gas_state.set_composition(gas_comp);
-                    gas_state.p = 101.325;
-                    gas_state.t = 288.706;
-                    gas_state.density();
-                    gas_state.properties();
-                    let density_kg_m3 = gas_state.mm * gas_state.d;
-                    let density_kg_ft3 = density_kg_m3 / density_kg_m3;
-                    kg_s = density_kg_ft3 * scfs;
-                }
-                kg_s
+                let density_kg_m3 = std_density(gas_comp, model, 288.706);
+                let density_kg_ft3 = density_kg_m3 / 35.3147;
+                density_kg_ft3 * scfs
             } else {
-                let scfs = flow / 60.0;
+                let scfs = flow / 3600.0;
                 // Calculate density at STP
-                let kg_s;
-                if use_gerg2008 {
-                    let mut gas_state = gerg2008::Gerg2008::new();
-                    gas_state.set_composition(gas_comp);
-                    gas_state.p = 101.325;
-                    gas_state.t = 294.261;
-                    gas_state.density(0);
-                    gas_state.properties();
-                    let density_kg_m3 = gas_state.mm * gas_state.d;
-                    let density_kg_ft3 = density_kg_m3 / density_kg_m3;
-                    kg_s = density_kg_ft3 * scfs;
-                } else {
-                    let mut gas_state = aga8::detail::Detail::new();
-                    gas_state.set_composition(gas_comp);
-                    gas_state.p = 101.325;
-                    gas_state.t = 294.261;
-                    gas_state.density();
-                    gas_state.properties();
-                    let density_kg_m3 = gas_state.mm * gas_state.d;
-                    let density_kg_ft3 = density_kg_m3 / density_kg_m3;
-                    kg_s = density_kg_ft3 * scfs;
-                }
-                kg_s
+                let density_kg_m3 = std_density(gas_comp, model, 294.261);
+                let density_kg_ft3 = density_kg_m3 / 35.3147;
+                density_kg_ft3 * scfs
             }
         },
     }
-}
\ No newline at end of file
+}
+pub fn get_length(length_m: f64, unit: Length) -> f64 {
+    match unit {
+        Length::mm => length_m * 1000.0,
+        Length::cm => length_m * 100.0,
+        Length::m => length_m,
+        Length::inch => length_m / 0.0254,
+        Length::ft => length_m / 0.3048,
+    }
+}
+
+pub fn set_length(length: f64, unit: Length) -> f64 {
+    match unit {
+        Length::mm => length / 1000.0,
+        Length::cm => length / 100.0,
+        Length::m => length,
+        Length::inch => length * 0.0254,
+        Length::ft => length * 0.3048,
+    }
+}
+
+pub fn get_distance(distance_m: f64, unit: Length) -> f64 {
+    match unit {
+        Length::mm | Length::cm | Length::m => distance_m,
+        Length::inch | Length::ft => distance_m / 0.3048,
+    }
+}
+
+pub fn set_distance(distance: f64, unit: Length) -> f64 {
+    match unit {
+        Length::mm | Length::cm | Length::m => distance,
+        Length::inch | Length::ft => distance * 0.3048,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gas::{
+        get_gas_comp,
+        Gas,
+    };
+
+    #[test]
+    fn flow_round_trip() {
+        let gas_comp = get_gas_comp(Gas::Air);
+        let units = [
+            Flow::kg_s,
+            Flow::kg_m,
+            Flow::kg_h,
+            Flow::lbm_s,
+            Flow::lbm_m,
+            Flow::lbm_h,
+            Flow::Nm3_h,
+            Flow::scfm,
+            Flow::scfh,
+        ];
+        for stp_60 in [true, false] {
+            for unit in units {
+                let flow = get_flow(2.5, unit, &gas_comp, stp_60, Model::Gerg2008);
+                let flow_kg_s = set_flow(flow, unit, &gas_comp, stp_60, Model::Gerg2008);
+                assert!((flow_kg_s / 2.5 - 1.0).abs() < 1.0e-12, "{} {}", unit.print_unit(), flow_kg_s);
+            }
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use ratatui::widgets::ListItem;
+use ratatui::
+    style::{
+        Color,
+        Stylize
+    };
+
+use crate::units::{
+    self,
+    PrintUnit,
+};
+use crate::App;
+
+// A logged verification against the EOS speed of sound
+pub struct Check {
+    pub p: f64,
+    pub t: f64,
+    pub w_eos: f64,
+    pub w_meter: f64,
+    pub deviation: f64,
+    pub pass: bool,
+}
+
+// Speed of sound (m/s) at the current state from the selected EOS
+pub fn eos_speed(app: &App) -> f64 {
+    app.cur_state.w
+}
+
+// Percentage deviation of a measured speed of sound from the EOS value
+pub fn deviation(w_meter: f64, w_eos: f64) -> f64 {
+    (w_meter - w_eos) / w_eos * 100.0
+}
+
+// Average speed of sound over the meter paths
+pub fn path_average(app: &App) -> Option<f64> {
+    if app.usm_paths.is_empty() {
+        return None
+    }
+    Some(app.usm_paths.iter().sum::<f64>() / app.usm_paths.len() as f64)
+}
+
+// Logs the path average against the EOS speed of sound at the current state
+pub fn log_check(app: &mut App) {
+    let w_meter = match path_average(app) {
+        Some(w) => w,
+        None => return,
+    };
+    let w_eos = eos_speed(app);
+    let dev = deviation(w_meter, w_eos);
+    let (p, t) = (app.cur_state.p, app.cur_state.t);
+    app.usm_log.push(Check {
+        p,
+        t,
+        w_eos,
+        w_meter,
+        deviation: dev,
+        pass: dev.abs() <= app.usm_tolerance,
+    });
+}
+
+pub fn set_param(app: &mut App, val: f64) {
+    match app.input_param {
+        1 => app.usm_paths.push(units::set_speed(val, app.units.speed)),
+        2 => app.usm_tolerance = val.abs(),
+        _ => {}
+    }
+}
+
+pub fn run_usm(app: &App) -> Vec<ListItem<'static>> {
+    let p_str = app.units.pressure.print_unit();
+    let t_str = app.units.temp.print_unit();
+    let speed_str = app.units.speed.print_unit();
+    let w_eos = eos_speed(app);
+    let pass_color = |dev: f64| if dev.abs() <= app.usm_tolerance { Color::LightGreen } else { Color::Red };
+
+    let mut items = vec![
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "2-Tolerance:", app.usm_tolerance, "%",
+            )
+        )
+            .fg(Color::LightYellow)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "EOS Speed of Sound:", units::get_speed(w_eos, app.units.speed), speed_str,
+            )
+        )
+            .fg(Color::White)
+            .bg(Color::Black),
+    ];
+
+    for (i, w) in app.usm_paths.iter().enumerate() {
+        let dev = deviation(*w, w_eos);
+        items.push(
+            ListItem::new(
+                format!("{:<24} {:.4} {}  {:+.4} %",
+                    format!("Path {}:", i + 1), units::get_speed(*w, app.units.speed), speed_str, dev,
+                )
+            )
+                .fg(pass_color(dev))
+                .bg(Color::Black)
+        );
+    }
+
+    match path_average(app) {
+        Some(w_avg) => {
+            let dev = deviation(w_avg, w_eos);
+            let result = if dev.abs() <= app.usm_tolerance { "PASS" } else { "FAIL" };
+            items.push(
+                ListItem::new(
+                    format!("{:<24} {:.4} {}  {:+.4} %  {}",
+                        "Path Average:", units::get_speed(w_avg, app.units.speed), speed_str, dev, result,
+                    )
+                )
+                    .fg(pass_color(dev))
+                    .bg(Color::Black)
+            );
+        },
+        None => {
+            items.push(
+                ListItem::new("Enter the measured speed of sound for each path (1)")
+                    .fg(Color::Red)
+                    .bg(Color::Black)
+            );
+        },
+    }
+
+    if app.usm_log.is_empty() {
+        return items
+    }
+    items.push(
+        ListItem::new("Verification Log")
+            .fg(Color::White)
+            .bg(Color::Black)
+    );
+    for (i, check) in app.usm_log.iter().enumerate() {
+        items.push(
+            ListItem::new(
+                format!("#{:<3} {:.4} {}  {:.4} {}  EOS {:.4}  Meter {:.4} {}  {:+.4} %  {}",
+                    i + 1,
+                    units::get_pressure(check.p, app.units.pressure), p_str,
+                    units::get_temperature(check.t, app.units.temp), t_str,
+                    units::get_speed(check.w_eos, app.units.speed),
+                    units::get_speed(check.w_meter, app.units.speed), speed_str,
+                    check.deviation,
+                    if check.pass { "PASS" } else { "FAIL" },
+                )
+            )
+                .fg(if check.pass { Color::LightGreen } else { Color::Red })
+                .bg(Color::Black)
+        );
+    }
+    items
+}
@@ -0,0 +1,176 @@
+use std::f64::consts::PI;
+
+use ratatui::widgets::ListItem;
+use ratatui::
+    style::{
+        Color,
+        Stylize
+    };
+
+use crate::flash::{
+    self,
+    GasProps,
+};
+use crate::throttle;
+use crate::units::{
+    self,
+    PrintUnit,
+};
+use crate::App;
+
+// IEC 60534-2-1 numerical constant for Kv with pressure in kPa and mass flow in kg/h
+const N8_KV: f64 = 0.948;
+// Cv per unit Kv
+const CV_PER_KV: f64 = 1.156;
+
+pub struct ValveResult {
+    pub inlet: GasProps,
+    pub outlet: GasProps,
+    pub kappa: f64,
+    pub z: f64,
+    pub x: f64,
+    // Pressure drop ratio limit Fgamma xTP
+    pub x_choked: f64,
+    pub choked: bool,
+    pub y: f64,
+    pub kv: f64,
+    // Outlet velocity in m/s
+    pub velocity: f64,
+}
+
+pub fn size_valve(app: &App) -> ValveResult {
+    let inlet = throttle::throttle_inlet(app);
+    let outlet = flash::props_ph(&app.gas_comp, app.model, app.valve_p2, inlet.h, inlet.t);
+    let (kappa, z) = (inlet.kappa, inlet.z);
+
+    // Piping geometry factor applied to xT without the inlet reducer term
+    let xtp = app.valve_xt / (app.valve_fp * app.valve_fp);
+    let f_gamma = kappa / 1.40;
+    let x = (inlet.p - app.valve_p2) / inlet.p;
+    let x_choked = f_gamma * xtp;
+    let choked = x >= x_choked;
+    let x_sizing = x.min(x_choked);
+    let y = 1.0 - x_sizing / (3.0 * x_choked);
+
+    let w_kg_h = app.flow_val * 3600.0;
+    let kv = if x_sizing > 0.0 {
+        w_kg_h / (N8_KV * app.valve_fp * inlet.p * y * (x_sizing * inlet.mm / (inlet.t * z)).sqrt())
+    } else {
+        0.0
+    };
+
+    let area = PI / 4.0 * app.valve_pipe_d * app.valve_pipe_d;
+    let velocity = app.flow_val / (outlet.d * outlet.mm * area);
+
+    ValveResult {
+        inlet,
+        outlet,
+        kappa,
+        z,
+        x,
+        x_choked,
+        choked,
+        y,
+        kv,
+        velocity,
+    }
+}
+
+pub fn set_param(app: &mut App, val: f64) {
+    match app.input_param {
+        1 => app.valve_p2 = units::set_pressure(val, app.units.pressure),
+        2 => app.valve_xt = val,
+        3 => app.valve_fp = val,
+        4 => app.valve_pipe_d = units::set_length(val, app.units.length),
+        _ => {}
+    }
+}
+
+pub fn run_valve(app: &App) -> Vec<ListItem<'static>> {
+    let p_str = app.units.pressure.print_unit();
+    let t_str = app.units.temp.print_unit();
+    let speed_str = app.units.speed.print_unit();
+    let flow_str = app.units.flow.print_unit();
+
+    let mut items = vec![
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "1-Outlet Pressure:", units::get_pressure(app.valve_p2, app.units.pressure), p_str,
+            )
+        )
+            .fg(Color::LightYellow)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "2-Valve xT:", app.valve_xt, "[]",
+            )
+        )
+            .fg(Color::LightYellow)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "3-Piping Factor Fp:", app.valve_fp, "[]",
+            )
+        )
+            .fg(Color::LightYellow)
+            .bg(Color::Black),
+
+        ListItem::new(
+            format!("{:<24} {:.4} {}",
+                "4-Outlet Pipe Diameter:", units::get_length(app.valve_pipe_d, app.units.length), app.units.length.print_unit(),
+            )
+        )
+            .fg(Color::LightYellow)
+            .bg(Color::Black),
+    ];
+
+    let result = size_valve(app);
+    if result.x <= 0.0 {
+        items.push(
+            ListItem::new("Outlet pressure must be below the inlet pressure")
+                .fg(Color::Red)
+                .bg(Color::Black)
+        );
+        return items
+    }
+
+    let choked_text = if result.choked { "Yes" } else { "No" };
+    let choked_color = if result.choked { Color::Red } else { Color::LightCyan };
+    let rows = [
+        ("Flow:", units::get_flow(app.flow_val, app.units.flow, &app.gas_comp, app.stp_60_F, app.model), flow_str, Color::White),
+        ("Inlet Press:", units::get_pressure(result.inlet.p, app.units.pressure), p_str, Color::White),
+        ("Inlet Temp:", units::get_temperature(result.inlet.t, app.units.temp), t_str, Color::White),
+        ("Inlet Kappa:", result.kappa, "[]", Color::White),
+        ("Inlet Z:", result.z, "[]", Color::White),
+        ("Pressure Drop Ratio x:", result.x, "[]", Color::LightCyan),
+        ("Choked Ratio Fy xTP:", result.x_choked, "[]", Color::LightCyan),
+        ("Expansion Factor Y:", result.y, "[]", Color::LightCyan),
+        ("Required Kv:", result.kv, "m^3/hr", Color::LightCyan),
+        ("Required Cv:", result.kv * CV_PER_KV, "gpm", Color::LightCyan),
+        ("Outlet Temp:", units::get_temperature(result.outlet.t, app.units.temp), t_str, Color::LightCyan),
+        ("Outlet Velocity:", units::get_speed(result.velocity, app.units.speed), speed_str, Color::LightCyan),
+    ];
+    for (label, val, unit, color) in rows {
+        items.push(
+            ListItem::new(
+                format!("{:<24} {:.4} {}",
+                    label, val, unit,
+                )
+            )
+                .fg(color)
+                .bg(Color::Black)
+        );
+    }
+    items.push(
+        ListItem::new(
+            format!("{:<24} {}",
+                "Choked Flow:", choked_text,
+            )
+        )
+            .fg(choked_color)
+            .bg(Color::Black)
+    );
+    items
+}